@@ -1,11 +1,97 @@
 use std::str::Lines;
 use std::collections::HashMap;
 
+#[derive(Debug, PartialEq)]
+pub struct Repeat {
+    pub frequency: i32,
+    pub positions: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct FrequencyTrace {
+    changes: Vec<i32>,
+}
+
+impl FrequencyTrace {
+    pub fn new(lines: Lines) -> FrequencyTrace {
+        let changes = lines.map(parse_change).collect();
+
+        FrequencyTrace { changes }
+    }
+
+    pub fn drift(&self) -> i32 {
+        self.changes.iter().sum()
+    }
+
+    // Every frequency reached within the given amount of cycles, in order.
+    pub fn frequencies(&self, cycles: usize) -> Vec<i32> {
+        let mut freq = 0;
+
+        self.changes
+            .iter()
+            .cycle()
+            .take(self.changes.len() * cycles)
+            .map(|change| {
+                freq += change;
+                freq
+            })
+            .collect()
+    }
+
+    // Frequencies that are reached more than once within the given amount of cycles,
+    // ordered by the moment they are reached for the second time.
+    pub fn repeats(&self, cycles: usize) -> Vec<Repeat> {
+        let mut positions: HashMap<i32, Vec<usize>> = HashMap::new();
+
+        for (position, freq) in self.frequencies(cycles).into_iter().enumerate() {
+            positions.entry(freq).or_default().push(position);
+        }
+
+        let mut repeats: Vec<Repeat> = positions
+            .into_iter()
+            .filter(|(_, positions)| positions.len() > 1)
+            .map(|(frequency, positions)| Repeat { frequency, positions })
+            .collect();
+
+        repeats.sort_by_key(|repeat| repeat.positions[1]);
+
+        repeats
+    }
+
+    pub fn min_frequency(&self, cycles: usize) -> Option<i32> {
+        self.frequencies(cycles).into_iter().min()
+    }
+
+    pub fn max_frequency(&self, cycles: usize) -> Option<i32> {
+        self.frequencies(cycles).into_iter().max()
+    }
+
+    // Frequency after change i in cycle c is c * drift + prefix[i], so two changes
+    // can only ever land on the same frequency when their prefixes share a remainder.
+    pub fn will_repeat(&self) -> bool {
+        let drift = self.drift();
+
+        if drift == 0 {
+            return !self.changes.is_empty();
+        }
+
+        let mut remainders = HashMap::new();
+
+        self.frequencies(1)
+            .into_iter()
+            .any(|freq| remainders.insert(freq.rem_euclid(drift.abs()), freq).is_some())
+    }
+}
+
+fn parse_change(line: &str) -> i32 {
+    line.trim().parse::<i32>().unwrap()
+}
+
 pub fn calibrate_frequency(lines: Lines) -> i32 {
     let mut freq = 0;
 
     for line in lines {
-        let change = line.to_string().trim().parse::<i32>().unwrap();
+        let change = parse_change(line);
         freq += change;
     }
 
@@ -19,7 +105,7 @@ pub fn find_first_duplicate(lines: Lines) -> i32 {
 
     let mut freq = 0;
     for line in iter {
-        let change = line.to_string().trim().parse::<i32>().unwrap();
+        let change = parse_change(line);
         freq += change;
 
         if frequencies.contains_key(&freq) {
@@ -36,6 +122,8 @@ pub fn find_first_duplicate(lines: Lines) -> i32 {
 mod tests {
     use calibrate_frequency;
     use find_first_duplicate;
+    use FrequencyTrace;
+    use Repeat;
 
     #[test]
     fn it_should_add_changes_to_frequency() {
@@ -102,4 +190,61 @@ mod tests {
         // Assert
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn it_should_trace_repeating_frequencies() {
+
+        // Arrange
+        let input = "+1
+        +1
+        +1
+        +1
+        -5";
+
+        // Act
+        let trace = FrequencyTrace::new(input.lines());
+        let repeats = trace.repeats(2);
+
+        // Assert
+        assert_eq!(trace.drift(), -1);
+        assert_eq!(repeats[0], Repeat { frequency: 1, positions: vec![0, 6] });
+        assert_eq!(repeats.len(), 3);
+        assert_eq!(trace.min_frequency(2), Some(-2));
+        assert_eq!(trace.max_frequency(2), Some(4));
+        assert!(trace.will_repeat());
+    }
+
+    #[test]
+    fn it_should_detect_sequences_that_repeat() {
+
+        // Arrange
+        let input = "+7
+        +7
+        -2
+        -7
+        -4";
+
+        // Act
+        let trace = FrequencyTrace::new(input.lines());
+
+        // Assert
+        assert_eq!(trace.drift(), 1);
+        assert!(trace.will_repeat());
+        assert!(FrequencyTrace::new("+1\n-1".lines()).will_repeat());
+    }
+
+    #[test]
+    fn it_should_detect_sequences_that_never_repeat() {
+
+        // Arrange
+        let input = "+1
+        +2";
+
+        // Act
+        let trace = FrequencyTrace::new(input.lines());
+
+        // Assert
+        assert_eq!(trace.drift(), 3);
+        assert!(!trace.will_repeat());
+    }
 }
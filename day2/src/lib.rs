@@ -1,25 +1,69 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::iter::FromIterator;
+
+#[derive(Debug, PartialEq)]
+pub struct IdBreakdown<'a> {
+    pub id: &'a str,
+    pub histogram: HashMap<char, i32>,
+    pub matched_counts: Vec<i32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ChecksumReport<'a> {
+    pub ids: Vec<IdBreakdown<'a>>,
+    pub totals: Vec<(i32, i32)>,
+    pub checksum: i32,
+}
 
 pub fn get_checksum(string: &str) -> i32 {
-    let mut doubles = 0;
-    let mut triples = 0;
+    get_checksum_report(string, &[2, 3], |totals| totals.iter().product()).checksum
+}
 
-    for line in string.lines() {
-        let double_chars = has_char_counts(line, 2);
-        let triple_chars = has_char_counts(line, 3);
+// For every count in `counts` tallies how many ids contain some letter exactly that
+// many times, then hands those tallies (in the order of `counts`) to `combine`.
+pub fn get_checksum_report<'a, F>(string: &'a str, counts: &[i32], combine: F) -> ChecksumReport<'a>
+    where F: Fn(&[i32]) -> i32 {
+    let ids: Vec<IdBreakdown> = string.lines()
+        .map(|id| id.trim())
+        .map(|id| {
+            let histogram = get_char_counts(id);
+            let matched_counts = counts
+                .iter()
+                .filter(|count| histogram.values().any(|value| value == *count))
+                .cloned()
+                .collect();
+
+            IdBreakdown { id, histogram, matched_counts }
+        })
+        .collect();
 
-        if double_chars {
-            doubles += 1;
-        }
+    let totals: Vec<(i32, i32)> = counts
+        .iter()
+        .map(|count| {
+            let total = ids
+                .iter()
+                .filter(|breakdown| breakdown.matched_counts.contains(count))
+                .count();
 
-        if triple_chars {
-            triples += 1;
-        }
+            (*count, total as i32)
+        })
+        .collect();
+
+    let tallies: Vec<i32> = totals.iter().map(|&(_, total)| total).collect();
+    let checksum = combine(&tallies);
+
+    ChecksumReport { ids, totals, checksum }
+}
+
+pub fn get_char_counts(id: &str) -> HashMap<char, i32> {
+    let mut chars: HashMap<char, i32> = HashMap::new();
+
+    for c in id.chars() {
+        let amount = chars.entry(c).or_insert(0);
+
+        *amount += 1;
     }
 
-    doubles * triples
+    chars
 }
 
 pub fn get_similar_chars(id1: &str, id2: &str) -> Vec<char> {
@@ -40,42 +84,159 @@ pub fn count_not_similar_chars(id1: &str, id2: &str) -> usize {
         .iter()
         .count();
 
-    id1.len() - similar_chars_len
+    id1.chars().count() - similar_chars_len
+}
+
+// Unlike `count_not_similar_chars` this refuses to compare ids of different lengths.
+pub fn hamming_distance(id1: &str, id2: &str) -> Option<usize> {
+    match id1.chars().count() == id2.chars().count() {
+        true => Some(count_not_similar_chars(id1, id2)),
+        false => None
+    }
 }
 
-pub fn get_similar_ids(input: &str) -> HashSet<&str> {
-    let result: Vec<Vec<&str>> = input.lines()
+#[derive(Debug, PartialEq)]
+pub struct IdDistance<'a> {
+    pub first: &'a str,
+    pub second: &'a str,
+    pub distance: usize,
+}
+
+fn parse_ids(input: &str) -> Vec<&str> {
+    input.lines()
         .map(|id| id.trim())
-        .map(|id1: &str| {
-            input.lines()
-                .map(|id| id.trim())
-                .filter_map(|id2: &str| {
-                    match count_not_similar_chars(id1, id2) {
-                        1 => Some(id1),
-                        _ => None
-                    }
-                })
-                .collect()
-        })
-        .collect();
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+fn get_distances(ids: &[&str]) -> Vec<(usize, usize, usize)> {
+    let mut distances = vec![];
 
-    let result2: Vec<&str> = result.into_iter().flatten().collect();
+    for (a, id1) in ids.iter().enumerate() {
+        for (b, id2) in ids.iter().enumerate().skip(a + 1) {
+            if let Some(distance) = hamming_distance(id1, id2) {
+                distances.push((a, b, distance));
+            }
+        }
+    }
 
-    HashSet::from_iter(result2)
+    distances
 }
 
-pub fn has_char_counts(id: &str, count: i32) -> bool {
-    let mut chars: HashMap<char, i32> = HashMap::new();
+pub fn get_ids_within_distance(input: &str, max_distance: usize) -> Vec<IdDistance<'_>> {
+    let ids = parse_ids(input);
 
-    for c in id.chars() {
-        let amount = chars.entry(c).or_insert(0);
+    get_distances(&ids)
+        .into_iter()
+        .filter(|&(_, _, distance)| distance <= max_distance)
+        .map(|(a, b, distance)| IdDistance { first: ids[a], second: ids[b], distance })
+        .collect()
+}
 
-        *amount += 1;
+pub fn get_closest_ids(input: &str) -> Option<IdDistance<'_>> {
+    let ids = parse_ids(input);
+
+    get_distances(&ids)
+        .into_iter()
+        .min_by_key(|&(a, b, distance)| (distance, a, b))
+        .map(|(a, b, distance)| IdDistance { first: ids[a], second: ids[b], distance })
+}
+
+fn find_root(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    let mut current = index;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+
+    root
+}
+
+// Groups ids that are connected through a chain of ids at most `max_distance` apart.
+// Ids with a length that matches no other id end up in a cluster of their own.
+pub fn get_id_clusters(input: &str, max_distance: usize) -> Vec<Vec<&str>> {
+    let ids = parse_ids(input);
+    let mut parents: Vec<usize> = (0..ids.len()).collect();
+
+    for (a, b, distance) in get_distances(&ids) {
+        if distance <= max_distance {
+            let root_a = find_root(&mut parents, a);
+            let root_b = find_root(&mut parents, b);
+            parents[root_b.max(root_a)] = root_a.min(root_b);
+        }
+    }
+
+    let mut clusters: Vec<Vec<&str>> = vec![];
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::new();
+
+    for (index, id) in ids.iter().enumerate() {
+        let root = find_root(&mut parents, index);
+        let cluster = *cluster_of_root.entry(root).or_insert_with(|| {
+            clusters.push(vec![]);
+            clusters.len() - 1
+        });
+
+        clusters[cluster].push(id);
     }
 
-    chars.retain(|_, value| value == &count);
+    clusters
+}
 
-    chars.len() > 0
+#[derive(Debug, PartialEq)]
+pub struct SimilarPair<'a> {
+    pub first: &'a str,
+    pub second: &'a str,
+    pub index: usize,
+    pub common: String,
+}
+
+// Buckets every id by itself with one position masked out. Two ids only end up in
+// the same bucket when they are equal apart from that position.
+pub fn get_similar_ids(input: &str) -> Vec<SimilarPair<'_>> {
+    let ids: Vec<&str> = input.lines().map(|id| id.trim()).collect();
+    let mut buckets: HashMap<(usize, String), Vec<usize>> = HashMap::new();
+
+    for (id_index, id) in ids.iter().enumerate() {
+        for (index, (start, c)) in id.char_indices().enumerate() {
+            let masked = format!("{}{}", &id[..start], &id[start + c.len_utf8()..]);
+
+            buckets.entry((index, masked)).or_default().push(id_index);
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize, usize, String)> = vec![];
+
+    for ((index, common), bucket) in buckets {
+        for (i, &a) in bucket.iter().enumerate() {
+            for &b in &bucket[i + 1..] {
+                if ids[a] != ids[b] {
+                    pairs.push((a, b, index, common.clone()));
+                }
+            }
+        }
+    }
+
+    pairs.sort();
+
+    pairs
+        .into_iter()
+        .map(|(a, b, index, common)| SimilarPair {
+            first: ids[a],
+            second: ids[b],
+            index,
+            common,
+        })
+        .collect()
+}
+
+pub fn has_char_counts(id: &str, count: i32) -> bool {
+    get_char_counts(id).values().any(|value| value == &count)
 }
 
 
@@ -84,8 +245,13 @@ mod tests {
     use get_checksum;
     use count_not_similar_chars;
     use get_similar_ids;
-    use std::collections::HashSet;
-    use std::iter::FromIterator;
+    use SimilarPair;
+    use hamming_distance;
+    use get_ids_within_distance;
+    use get_closest_ids;
+    use get_id_clusters;
+    use IdDistance;
+    use get_checksum_report;
 
     #[test]
     fn it_should_calculate_the_product_of_doubles_and_triples() {
@@ -213,7 +379,12 @@ mod tests {
         fguij
         axcye
         wvxyz";
-        let expected_result: HashSet<&str> = HashSet::from_iter(vec!["fguij", "fghij"]);
+        let expected_result = vec![SimilarPair {
+            first: "fghij",
+            second: "fguij",
+            index: 2,
+            common: "fgij".to_string(),
+        }];
 
         // Act
         let result = get_similar_ids(input);
@@ -221,4 +392,83 @@ mod tests {
         // Assert
         assert_eq!(result, expected_result);
     }
+
+    #[test]
+    fn it_should_not_compare_ids_of_different_length() {
+
+        // Arrange
+        let input1 = "abcd";
+        let input2 = "abc";
+
+        // Act
+        let result = hamming_distance(input1, input2);
+
+        // Assert
+        assert_eq!(result, None);
+        assert_eq!(hamming_distance("abcd", "abzz"), Some(2));
+    }
+
+    #[test]
+    fn it_should_find_ids_within_distance() {
+
+        // Arrange
+        let input = "abcde
+        abcdf
+        abcxy
+        zzzzz
+        abc";
+
+        // Act
+        let result = get_ids_within_distance(input, 2);
+
+        // Assert
+        assert_eq!(result, vec![
+            IdDistance { first: "abcde", second: "abcdf", distance: 1 },
+            IdDistance { first: "abcde", second: "abcxy", distance: 2 },
+            IdDistance { first: "abcdf", second: "abcxy", distance: 2 },
+        ]);
+        assert_eq!(get_closest_ids(input), Some(IdDistance { first: "abcde", second: "abcdf", distance: 1 }));
+    }
+
+    #[test]
+    fn it_should_cluster_ids_connected_by_distance() {
+
+        // Arrange
+        let input = "aaaa
+        zzzz
+        aaab
+        abbb
+        aabb
+        abc";
+
+        // Act
+        let result = get_id_clusters(input, 1);
+
+        // Assert
+        assert_eq!(result, vec![
+            vec!["aaaa", "aaab", "abbb", "aabb"],
+            vec!["zzzz"],
+            vec!["abc"],
+        ]);
+    }
+
+    #[test]
+    fn it_should_report_checksums_for_arbitrary_counts() {
+
+        // Arrange
+        let input = "aabbbcccc
+        abcd
+        aaaab
+        aabc";
+
+        // Act
+        let result = get_checksum_report(input, &[2, 3, 4], |totals| totals.iter().sum());
+
+        // Assert
+        assert_eq!(result.totals, vec![(2, 2), (3, 1), (4, 2)]);
+        assert_eq!(result.checksum, 5);
+        assert_eq!(result.ids[0].matched_counts, vec![2, 3, 4]);
+        assert_eq!(result.ids[1].matched_counts, Vec::<i32>::new());
+        assert_eq!(result.ids[2].histogram[&'a'], 4);
+    }
 }
@@ -1,45 +1,304 @@
 use std::collections::HashMap;
+use std::io::{self, Write};
 use regex::Regex;
 
 extern crate regex;
 
-const FABRIC_LENGTH: i32 = 1000;
+pub type FabricMap = HashMap<(i64, i64), i32>;
 
 #[derive(Debug)]
 pub struct Claim {
     pub id: i32,
-    pub left: i32,
-    pub right: i32,
-    pub top: i32,
-    pub bottom: i32,
+    pub left: i64,
+    pub right: i64,
+    pub top: i64,
+    pub bottom: i64,
 }
 
-pub fn plot_square(fabric: &mut HashMap<i32, i32>, claim: &Claim) {
+impl Claim {
+    // Claims without an area cover no fabric, so they never overlap and are always uncontested.
+    pub fn overlaps(&self, other: &Claim) -> bool {
+        !self.is_empty() && !other.is_empty() &&
+            self.left < other.right && other.left < self.right &&
+            self.top < other.bottom && other.top < self.bottom
+    }
+
+    pub fn shared_area(&self, other: &Claim) -> i64 {
+        let width = self.right.min(other.right) - self.left.max(other.left);
+        let height = self.bottom.min(other.bottom) - self.top.max(other.top);
+
+        width.max(0) * height.max(0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.left >= self.right || self.top >= self.bottom
+    }
+}
+
+// Centered interval tree over the horizontal extent of the claims. Every node keeps the
+// claims that contain its center, once sorted by left edge and once by right edge, so a
+// query can stop scanning as soon as the remaining claims can no longer reach it.
+#[derive(Debug)]
+struct IntervalNode {
+    center: i64,
+    by_left: Vec<usize>,
+    by_right: Vec<usize>,
+    lower: Option<Box<IntervalNode>>,
+    higher: Option<Box<IntervalNode>>,
+}
+
+impl IntervalNode {
+    fn new(claims: &[Claim], mut indices: Vec<usize>) -> Option<Box<IntervalNode>> {
+        if indices.is_empty() {
+            return None;
+        }
+
+        indices.sort_by_key(|&i| claims[i].left);
+        let center = claims[indices[indices.len() / 2]].left;
+
+        let (lower, rest): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&i| claims[i].right <= center);
+        let (higher, mut by_left): (Vec<usize>, Vec<usize>) = rest
+            .into_iter()
+            .partition(|&i| claims[i].left > center);
+
+        by_left.sort_by_key(|&i| claims[i].left);
+        let mut by_right = by_left.clone();
+        by_right.sort_by_key(|&i| -claims[i].right);
+
+        Some(Box::new(IntervalNode {
+            center,
+            by_left,
+            by_right,
+            lower: IntervalNode::new(claims, lower),
+            higher: IntervalNode::new(claims, higher),
+        }))
+    }
+
+    // Collects every claim whose horizontal extent intersects [start, end).
+    fn query(&self, claims: &[Claim], start: i64, end: i64, found: &mut Vec<usize>) {
+        if end <= self.center {
+            found.extend(self.by_left.iter().take_while(|&&i| claims[i].left < end));
+            if let Some(ref lower) = self.lower {
+                lower.query(claims, start, end, found);
+            }
+        } else if start > self.center {
+            found.extend(self.by_right.iter().take_while(|&&i| claims[i].right > start));
+            if let Some(ref higher) = self.higher {
+                higher.query(claims, start, end, found);
+            }
+        } else {
+            found.extend(self.by_left.iter());
+            if let Some(ref lower) = self.lower {
+                lower.query(claims, start, end, found);
+            }
+            if let Some(ref higher) = self.higher {
+                higher.query(claims, start, end, found);
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Overlap {
+    pub first: i32,
+    pub second: i32,
+    pub area: i64,
+}
+
+#[derive(Debug)]
+pub struct ClaimIndex<'a> {
+    claims: &'a [Claim],
+    ids: HashMap<i32, usize>,
+    root: Option<Box<IntervalNode>>,
+}
+
+impl<'a> ClaimIndex<'a> {
+    pub fn new(claims: &'a [Claim]) -> ClaimIndex<'a> {
+        let ids = claims
+            .iter()
+            .enumerate()
+            .map(|(index, claim)| (claim.id, index))
+            .collect();
+        let indices = (0..claims.len())
+            .filter(|&i| !claims[i].is_empty())
+            .collect();
+
+        ClaimIndex {
+            claims,
+            ids,
+            root: IntervalNode::new(claims, indices),
+        }
+    }
+
+    fn query(&self, left: i64, right: i64, top: i64, bottom: i64) -> Vec<&'a Claim> {
+        let mut found = vec![];
+        if let Some(ref root) = self.root {
+            root.query(self.claims, left, right, &mut found);
+        }
+        found.sort();
+
+        let claims = self.claims;
+        found
+            .into_iter()
+            .map(|i| &claims[i])
+            .filter(|claim| claim.top < bottom && top < claim.bottom)
+            .collect()
+    }
+
+    pub fn get_claim(&self, id: i32) -> Option<&'a Claim> {
+        let claims = self.claims;
+
+        self.ids.get(&id).map(|&index| &claims[index])
+    }
+
+    pub fn claims_at(&self, x: i64, y: i64) -> Vec<&'a Claim> {
+        self.query(x, x + 1, y, y + 1)
+    }
+
+    // Claims without an area cover no fabric, so they never overlap anything.
+    pub fn overlapping(&self, id: i32) -> Vec<&'a Claim> {
+        match self.get_claim(id) {
+            Some(claim) if claim.is_empty() => vec![],
+            Some(claim) => self.query(claim.left, claim.right, claim.top, claim.bottom)
+                .into_iter()
+                .filter(|other| other.id != id)
+                .collect(),
+            None => vec![]
+        }
+    }
+
+    pub fn shared_area(&self, first: i32, second: i32) -> Option<i64> {
+        let first = self.get_claim(first)?;
+        let second = self.get_claim(second)?;
+
+        Some(first.shared_area(second))
+    }
+
+    pub fn overlap_graph(&self) -> Vec<Overlap> {
+        let mut edges: Vec<Overlap> = self.claims
+            .iter()
+            .flat_map(|claim| {
+                self.overlapping(claim.id)
+                    .into_iter()
+                    .filter(move |other| claim.id < other.id)
+                    .map(move |other| Overlap {
+                        first: claim.id,
+                        second: other.id,
+                        area: claim.shared_area(other),
+                    })
+            })
+            .collect();
+        edges.sort_by_key(|edge| (edge.first, edge.second));
+
+        edges
+    }
+}
+
+pub fn plot_square(fabric: &mut FabricMap, claim: &Claim) {
     for x_coord in claim.left..claim.right {
         for y_coord in claim.top..claim.bottom {
-            let amount = fabric.entry(x_coord + (y_coord * FABRIC_LENGTH)).or_insert(0);
+            let amount = fabric.entry((x_coord, y_coord)).or_insert(0);
 
             *amount += 1;
         }
     }
 }
 
-pub fn count_overlap(fabric: &HashMap<i32, i32>, minimum: i32) -> usize {
+// Keeps track of which claims cover every square inch, so adding or withdrawing a claim
+// only touches the inches of that claim.
+#[derive(Debug, Default)]
+pub struct Fabric {
+    squares: HashMap<(i64, i64), Vec<i32>>,
+    claims: HashMap<i32, Claim>,
+    contested_squares: HashMap<i32, usize>,
+    overlap_area: usize,
+}
+
+impl Fabric {
+    pub fn new() -> Fabric {
+        Fabric::default()
+    }
+
+    pub fn add_claim(&mut self, claim: Claim) {
+        self.remove_claim(claim.id);
+
+        let mut contested = 0;
+        for x_coord in claim.left..claim.right {
+            for y_coord in claim.top..claim.bottom {
+                let owners = self.squares.entry((x_coord, y_coord)).or_default();
+
+                if owners.len() == 1 {
+                    self.overlap_area += 1;
+                    *self.contested_squares.get_mut(&owners[0]).unwrap() += 1;
+                }
+                if !owners.is_empty() {
+                    contested += 1;
+                }
+
+                owners.push(claim.id);
+            }
+        }
+
+        self.contested_squares.insert(claim.id, contested);
+        self.claims.insert(claim.id, claim);
+    }
+
+    pub fn remove_claim(&mut self, id: i32) -> Option<Claim> {
+        let claim = self.claims.remove(&id)?;
+        self.contested_squares.remove(&id);
+
+        for x_coord in claim.left..claim.right {
+            for y_coord in claim.top..claim.bottom {
+                let owners = self.squares.get_mut(&(x_coord, y_coord)).unwrap();
+                owners.retain(|&owner| owner != id);
+
+                if owners.len() == 1 {
+                    self.overlap_area -= 1;
+                    *self.contested_squares.get_mut(&owners[0]).unwrap() -= 1;
+                }
+                if owners.is_empty() {
+                    self.squares.remove(&(x_coord, y_coord));
+                }
+            }
+        }
+
+        Some(claim)
+    }
+
+    pub fn overlap_area(&self) -> usize {
+        self.overlap_area
+    }
+
+    pub fn uncontested_claims(&self) -> Vec<&Claim> {
+        let mut uncontested: Vec<&Claim> = self.contested_squares
+            .iter()
+            .filter(|&(_, contested)| *contested == 0)
+            .map(|(id, _)| &self.claims[id])
+            .collect();
+        uncontested.sort_by_key(|claim| claim.id);
+
+        uncontested
+    }
+}
+
+pub fn count_overlap(fabric: &FabricMap, minimum: i32) -> usize {
     fabric.into_iter().filter(|&(_, value)| {
         *value >= minimum
     }).count()
 }
 
 pub fn parse_line(line: &str) -> Claim {
-    let re = Regex::new(r"\#(\d+) @ (\d{1,3}),(\d{1,3}): (\d{1,3})x(\d{1,3})").unwrap();
+    let re = Regex::new(r"\#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
 
     let caps = re.captures(line).unwrap();
 
     let id = caps.get(1).unwrap().as_str().parse().unwrap();
     let x = caps.get(2).unwrap().as_str().parse().unwrap();
     let y = caps.get(3).unwrap().as_str().parse().unwrap();
-    let width = caps.get(4).unwrap().as_str().parse::<i32>().unwrap();
-    let height = caps.get(5).unwrap().as_str().parse::<i32>().unwrap();
+    let width = caps.get(4).unwrap().as_str().parse::<i64>().unwrap();
+    let height = caps.get(5).unwrap().as_str().parse::<i64>().unwrap();
 
     Claim {
         id,
@@ -50,13 +309,12 @@ pub fn parse_line(line: &str) -> Claim {
     }
 }
 
-pub fn find_non_overlapping<'a>(claims: &'a Vec<Claim>, fabric: &'a HashMap<i32, i32>) -> Option<&'a Claim> {
+pub fn find_non_overlapping<'a>(claims: &'a [Claim], fabric: &'a FabricMap) -> Option<&'a Claim> {
     claims.iter().find(|claim| {
         let mut overlap = false;
         for x_coord in claim.left..claim.right {
             for y_coord in claim.top..claim.bottom {
-                let index = x_coord + (y_coord * FABRIC_LENGTH);
-                let count = fabric.get(&index).unwrap();
+                let count = fabric.get(&(x_coord, y_coord)).unwrap();
                 overlap = overlap || *count != 1;
             }
         }
@@ -64,12 +322,176 @@ pub fn find_non_overlapping<'a>(claims: &'a Vec<Claim>, fabric: &'a HashMap<i32,
     })
 }
 
+// Sweeps a vertical line over the x coordinates where claims start or end. Between two
+// such coordinates the set of claims under the line is fixed, so the contested height of
+// that strip only has to be computed once.
+pub fn overlap_area(claims: &[Claim]) -> i64 {
+    let mut xs: Vec<i64> = claims
+        .iter()
+        .flat_map(|claim| vec![claim.left, claim.right])
+        .collect();
+    xs.sort();
+    xs.dedup();
+
+    xs.windows(2)
+        .map(|strip| {
+            let (start, end) = (strip[0], strip[1]);
+            let active: Vec<&Claim> = claims
+                .iter()
+                .filter(|claim| claim.left <= start && end <= claim.right)
+                .collect();
+
+            contested_length(&active) * (end - start)
+        })
+        .sum()
+}
+
+fn contested_length(claims: &[&Claim]) -> i64 {
+    let mut events: Vec<(i64, i32)> = claims
+        .iter()
+        .flat_map(|claim| vec![(claim.top, 1), (claim.bottom, -1)])
+        .collect();
+    events.sort();
+
+    let mut length = 0;
+    let mut depth = 0;
+    let mut previous = 0;
+
+    for (y, change) in events {
+        if depth >= 2 {
+            length += y - previous;
+        }
+
+        depth += change;
+        previous = y;
+    }
+
+    length
+}
+
+// Claims sorted by their left edge only have to be compared until the other claim
+// starts right of them.
+pub fn find_uncontested_claims(claims: &[Claim]) -> Vec<&Claim> {
+    let mut sorted: Vec<&Claim> = claims.iter().collect();
+    sorted.sort_by_key(|claim| claim.left);
+
+    let mut contested = vec![false; sorted.len()];
+
+    for (i, claim) in sorted.iter().enumerate() {
+        for (j, other) in sorted.iter().enumerate().skip(i + 1) {
+            if other.left >= claim.right {
+                break;
+            }
+
+            if claim.overlaps(other) {
+                contested[i] = true;
+                contested[j] = true;
+            }
+        }
+    }
+
+    let mut uncontested: Vec<&Claim> = sorted
+        .into_iter()
+        .zip(contested)
+        .filter(|&(_, contested)| !contested)
+        .map(|(claim, _)| claim)
+        .collect();
+    uncontested.sort_by_key(|claim| claim.id);
+
+    uncontested
+}
+
+#[derive(Debug, Default)]
+pub struct HeatmapOptions {
+    pub outlined: Vec<i32>,
+    pub highlight_uncontested: bool,
+}
+
+const OUTLINE_COLOUR: [u8; 3] = [255, 255, 255];
+const UNCONTESTED_COLOUR: [u8; 3] = [0, 200, 0];
+
+// Unclaimed fabric is black, a single claim is dark blue and overlaps run from red
+// to yellow as more claims pile up on the same square inch.
+fn heat_colour(count: i32, max_count: i32) -> [u8; 3] {
+    match count {
+        0 => [0, 0, 0],
+        1 => [0, 0, 120],
+        _ => {
+            let heat = (count - 2) * 255 / (max_count - 2).max(1);
+
+            [255, heat as u8, 0]
+        }
+    }
+}
+
+// Writes the fabric as a binary PPM image covering the bounding box of the claims.
+pub fn render_heatmap<W: Write>(fabric: &FabricMap, claims: &[Claim], options: &HeatmapOptions, out: &mut W) -> io::Result<()> {
+    let left = claims.iter().map(|claim| claim.left).min().unwrap_or(0);
+    let top = claims.iter().map(|claim| claim.top).min().unwrap_or(0);
+    let right = claims.iter().map(|claim| claim.right).max().unwrap_or(0);
+    let bottom = claims.iter().map(|claim| claim.bottom).max().unwrap_or(0);
+    let width = (right - left) as usize;
+    let max_count = fabric.values().cloned().max().unwrap_or(0);
+
+    let mut pixels: Vec<[u8; 3]> = vec![[0, 0, 0]; width * (bottom - top) as usize];
+    let index = |x: i64, y: i64| (x - left) as usize + (y - top) as usize * width;
+
+    for (&(x, y), &count) in fabric {
+        if x >= left && x < right && y >= top && y < bottom {
+            pixels[index(x, y)] = heat_colour(count, max_count);
+        }
+    }
+
+    // Squares missing from the fabric count as unclaimed, so claims that were never
+    // plotted into it are still highlighted when nothing else covers them.
+    if options.highlight_uncontested {
+        let uncontested = claims.iter().filter(|claim| {
+            (claim.left..claim.right).all(|x| {
+                (claim.top..claim.bottom).all(|y| fabric.get(&(x, y)).is_none_or(|&count| count <= 1))
+            })
+        });
+
+        for claim in uncontested {
+            for x in claim.left..claim.right {
+                for y in claim.top..claim.bottom {
+                    pixels[index(x, y)] = UNCONTESTED_COLOUR;
+                }
+            }
+        }
+    }
+
+    for claim in claims.iter().filter(|claim| options.outlined.contains(&claim.id)) {
+        for x in claim.left..claim.right {
+            for y in claim.top..claim.bottom {
+                let edge = x == claim.left || x == claim.right - 1 || y == claim.top || y == claim.bottom - 1;
+                if edge {
+                    pixels[index(x, y)] = OUTLINE_COLOUR;
+                }
+            }
+        }
+    }
+
+    write!(out, "P6\n{} {}\n255\n", width, bottom - top)?;
+    for pixel in pixels {
+        out.write_all(&pixel)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use plot_square;
     use std::collections::HashMap;
     use count_overlap;
     use parse_line;
+    use overlap_area;
+    use find_uncontested_claims;
+    use ClaimIndex;
+    use Overlap;
+    use Fabric;
+    use render_heatmap;
+    use HeatmapOptions;
 
     #[test]
     fn it_should_parse_a_line() {
@@ -89,7 +511,7 @@ mod tests {
         let mut fabric = HashMap::new();
         let claim = parse_line("#1 @ 10,10: 4x5");
 
-        plot_square(&mut fabric, claim);
+        plot_square(&mut fabric, &claim);
         let amount = count_overlap(&fabric, 1);
 
         assert_eq!(amount, 20);
@@ -102,11 +524,183 @@ mod tests {
         let claim2 = parse_line("#1 @ 3,1: 4x4");
 
 
-        plot_square(&mut fabric, claim1);
-        plot_square(&mut fabric, claim2);
+        plot_square(&mut fabric, &claim1);
+        plot_square(&mut fabric, &claim2);
 
         let amount = count_overlap(&fabric, 2);
 
         assert_eq!(amount, 4);
     }
+
+    #[test]
+    fn it_should_parse_large_coordinates() {
+        let claim = parse_line("#7 @ 123456,7: 2000x3");
+
+        assert_eq!(claim.left, 123456);
+        assert_eq!(claim.right, 123456 + 2000);
+        assert_eq!(claim.bottom, 10);
+    }
+
+    #[test]
+    fn it_should_sweep_overlapping_area() {
+        let claims = vec![
+            parse_line("#1 @ 1,3: 4x4"),
+            parse_line("#2 @ 3,1: 4x4"),
+            parse_line("#3 @ 5,5: 2x2"),
+        ];
+
+        let area = overlap_area(&claims);
+        let uncontested: Vec<i32> = find_uncontested_claims(&claims).iter().map(|c| c.id).collect();
+
+        assert_eq!(area, 4);
+        assert_eq!(uncontested, vec![3]);
+    }
+
+    #[test]
+    fn it_should_match_the_fabric_map() {
+        let claims = vec![
+            parse_line("#1 @ 0,0: 5x5"),
+            parse_line("#2 @ 2,2: 5x5"),
+            parse_line("#3 @ 4,0: 3x10"),
+            parse_line("#4 @ 1,1: 1x1"),
+        ];
+        let mut fabric = HashMap::new();
+        claims.iter().for_each(|claim| plot_square(&mut fabric, claim));
+
+        let area = overlap_area(&claims);
+
+        assert_eq!(area as usize, count_overlap(&fabric, 2));
+    }
+
+    #[test]
+    fn it_should_query_the_claim_index() {
+        let claims = vec![
+            parse_line("#1 @ 1,3: 4x4"),
+            parse_line("#2 @ 3,1: 4x4"),
+            parse_line("#3 @ 5,5: 2x2"),
+            parse_line("#4 @ 0,0: 10x1"),
+        ];
+
+        let index = ClaimIndex::new(&claims);
+        let at_point: Vec<i32> = index.claims_at(4, 4).iter().map(|c| c.id).collect();
+        let overlapping: Vec<i32> = index.overlapping(2).iter().map(|c| c.id).collect();
+
+        assert_eq!(at_point, vec![1, 2]);
+        assert_eq!(overlapping, vec![1]);
+        assert_eq!(index.shared_area(1, 2), Some(4));
+        assert_eq!(index.shared_area(1, 3), Some(0));
+        assert_eq!(index.shared_area(1, 99), None);
+        assert_eq!(index.overlap_graph(), vec![Overlap { first: 1, second: 2, area: 4 }]);
+    }
+
+    #[test]
+    fn it_should_not_index_claims_without_an_area() {
+        let claims = vec![
+            parse_line("#1 @ 8,1: 0x6"),
+            parse_line("#2 @ 5,0: 6x6"),
+            parse_line("#3 @ 7,2: 3x0"),
+        ];
+
+        let index = ClaimIndex::new(&claims);
+
+        assert_eq!(index.overlapping(1).len(), 0);
+        assert_eq!(index.overlapping(2).len(), 0);
+        assert_eq!(index.overlapping(3).len(), 0);
+        assert_eq!(index.overlap_graph(), vec![]);
+    }
+
+    #[test]
+    fn it_should_update_the_fabric_incrementally() {
+        let mut fabric = Fabric::new();
+
+        fabric.add_claim(parse_line("#1 @ 1,3: 4x4"));
+        fabric.add_claim(parse_line("#2 @ 3,1: 4x4"));
+        fabric.add_claim(parse_line("#3 @ 5,5: 2x2"));
+        let ids: Vec<i32> = fabric.uncontested_claims().iter().map(|c| c.id).collect();
+
+        assert_eq!(fabric.overlap_area(), 4);
+        assert_eq!(ids, vec![3]);
+
+        fabric.add_claim(parse_line("#4 @ 4,4: 2x2"));
+        assert_eq!(fabric.overlap_area(), 7);
+        assert_eq!(fabric.uncontested_claims().len(), 0);
+
+        fabric.remove_claim(1);
+        let ids: Vec<i32> = fabric.uncontested_claims().iter().map(|c| c.id).collect();
+
+        assert_eq!(fabric.overlap_area(), 3);
+        assert_eq!(ids, Vec::<i32>::new());
+        assert!(fabric.remove_claim(1).is_none());
+
+        fabric.remove_claim(4);
+        assert_eq!(fabric.overlap_area(), 0);
+        assert_eq!(fabric.uncontested_claims().len(), 2);
+    }
+
+    #[test]
+    fn it_should_treat_claims_without_an_area_as_uncontested() {
+        let lines = ["#1 @ 8,1: 0x6", "#2 @ 5,0: 6x6", "#3 @ 6,2: 1x1"];
+        let claims: Vec<_> = lines.iter().map(|line| parse_line(line)).collect();
+        let mut fabric = Fabric::new();
+        lines.iter().for_each(|line| fabric.add_claim(parse_line(line)));
+
+        let from_claims: Vec<i32> = find_uncontested_claims(&claims).iter().map(|c| c.id).collect();
+        let from_fabric: Vec<i32> = fabric.uncontested_claims().iter().map(|c| c.id).collect();
+
+        assert_eq!(from_claims, vec![1]);
+        assert_eq!(from_fabric, vec![1]);
+    }
+
+    #[test]
+    fn it_should_render_a_heatmap() {
+        let mut fabric = HashMap::new();
+        let claims = vec![
+            parse_line("#1 @ 0,0: 2x2"),
+            parse_line("#2 @ 1,1: 2x2"),
+            parse_line("#3 @ 3,0: 1x1"),
+        ];
+        claims.iter().for_each(|claim| plot_square(&mut fabric, claim));
+        let options = HeatmapOptions { outlined: vec![1], highlight_uncontested: true };
+        let mut image = vec![];
+
+        render_heatmap(&fabric, &claims, &options, &mut image).unwrap();
+        let header = b"P6\n4 3\n255\n";
+        let pixel = |x: usize, y: usize| {
+            let start = header.len() + (x + y * 4) * 3;
+            image[start..start + 3].to_vec()
+        };
+
+        assert_eq!(&image[..header.len()], &header[..]);
+        assert_eq!(image.len(), header.len() + 4 * 3 * 3);
+        assert_eq!(pixel(0, 0), vec![255, 255, 255]);
+        assert_eq!(pixel(2, 2), vec![0, 0, 120]);
+        assert_eq!(pixel(3, 0), vec![0, 200, 0]);
+        assert_eq!(pixel(3, 2), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn it_should_highlight_every_uncontested_claim() {
+        let mut fabric = HashMap::new();
+        let claims = vec![
+            parse_line("#1 @ 0,0: 1x1"),
+            parse_line("#2 @ 2,0: 1x1"),
+            parse_line("#3 @ 1,1: 1x1"),
+        ];
+        plot_square(&mut fabric, &claims[0]);
+        plot_square(&mut fabric, &claims[1]);
+        let options = HeatmapOptions { outlined: vec![], highlight_uncontested: true };
+        let mut image = vec![];
+
+        render_heatmap(&fabric, &claims, &options, &mut image).unwrap();
+        let header = b"P6\n3 2\n255\n";
+        let pixel = |x: usize, y: usize| {
+            let start = header.len() + (x + y * 3) * 3;
+            image[start..start + 3].to_vec()
+        };
+
+        assert_eq!(pixel(0, 0), vec![0, 200, 0]);
+        assert_eq!(pixel(2, 0), vec![0, 200, 0]);
+        assert_eq!(pixel(1, 1), vec![0, 200, 0]);
+        assert_eq!(pixel(1, 0), vec![0, 0, 0]);
+    }
 }
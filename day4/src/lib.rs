@@ -1,129 +1,358 @@
 extern crate chrono;
 extern crate regex;
 
+use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 use regex::Regex;
 use regex::Captures;
+use chrono::NaiveDate;
 use chrono::NaiveDateTime;
+use chrono::Duration;
 use chrono::Timelike;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 pub type GuardSleepMinutes = HashMap<u32, u32>;
 pub type GuardSleepInfo = HashMap<u32, GuardSleepMinutes>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum GuardEvent {
     StartOfShift { datetime: NaiveDateTime, id: u32 },
     Sleep { datetime: NaiveDateTime },
     WakeUp { datetime: NaiveDateTime },
 }
 
-pub fn get_most_sleeping_guard(sleep_info: &GuardSleepInfo) -> (u32, u32, u32) {
-    let most_sleepy_guard: (u32, u32, u32) = sleep_info
-        .iter()
-        .fold((0, 0, 0), |previous, (guard, sleep)| {
-            let total_sleep = sleep
-                .iter()
-                .fold(0, |total, (_minute, amount)| total + amount);
-
-            let most_sleepy_minute: (u32, u32) = sleep
-                .iter()
-                .fold((0, 0), |prev, next| {
-                    if prev.1 > *next.1 {
-                        prev
-                    } else {
-                        (*next.0, *next.1)
-                    }
-                });
+impl GuardEvent {
+    pub fn datetime(&self) -> &NaiveDateTime {
+        match self {
+            GuardEvent::StartOfShift { datetime, .. } => datetime,
+            GuardEvent::Sleep { datetime } => datetime,
+            GuardEvent::WakeUp { datetime } => datetime
+        }
+    }
+}
 
-            if previous.1 > total_sleep {
-                previous
-            } else {
-                (guard.clone(), total_sleep, most_sleepy_minute.0)
-            }
-        });
+impl fmt::Display for GuardEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let date = self.datetime().format("%Y-%m-%d %H:%M");
 
-    most_sleepy_guard
+        match self {
+            GuardEvent::StartOfShift { id, .. } => write!(f, "[{}] Guard #{} begins shift", date, id),
+            GuardEvent::Sleep { .. } => write!(f, "[{}] falls asleep", date),
+            GuardEvent::WakeUp { .. } => write!(f, "[{}] wakes up", date)
+        }
+    }
 }
 
-pub fn get_most_sleeped_minute(sleep_info: &GuardSleepInfo) -> (u32, u32, u32) {
-    let most_sleepy_guard: (u32, u32, u32) = sleep_info
-        .iter()
-        .fold((0, 0, 0), |previous, (guard, sleep)| {
-
-            let most_sleepy_minute: (u32, u32) = sleep
-                .iter()
-                .fold((0, 0), |prev, next| {
-                    if prev.1 > *next.1 {
-                        prev
-                    } else {
-                        (*next.0, *next.1)
-                    }
-                });
+#[derive(Debug, PartialEq)]
+pub struct Shift {
+    pub guard: u32,
+    pub date: NaiveDate,
+    pub naps: Vec<Range<NaiveDateTime>>,
+}
 
-            if previous.2 > most_sleepy_minute.1 {
-                previous
-            } else {
-                (guard.clone(), most_sleepy_minute.0, most_sleepy_minute.1)
-            }
-        });
+#[derive(Debug, PartialEq)]
+pub enum ShiftError {
+    NoGuardOnDuty { line: String },
+    OrphanWakeUp { line: String },
+    AlreadyAsleep { line: String },
+    UnterminatedNap { line: String },
+    DuplicateShift { line: String },
+}
 
-    most_sleepy_guard
+// Guards that start their shift in the evening are on duty for the next day.
+fn shift_date(datetime: &NaiveDateTime) -> NaiveDate {
+    match datetime.hour() {
+        0..=11 => datetime.date(),
+        _ => (*datetime + Duration::days(1)).date()
+    }
 }
 
-pub fn events_to_sleep_info(events: &Vec<GuardEvent>) -> GuardSleepInfo {
-    let mut current_guard: u32 = 0;
-    let mut fall_asleep_at: u32 = 0;
+pub fn nap_minutes(nap: &Range<NaiveDateTime>) -> Vec<NaiveDateTime> {
+    let mut minutes = vec![];
+    let mut minute = nap.start;
 
-    events.iter().fold(
+    while minute < nap.end {
+        minutes.push(minute);
+        minute += Duration::minutes(1);
+    }
+
+    minutes
+}
+
+fn sleep_histogram<F>(shifts: &[Shift], key: F) -> GuardSleepInfo
+    where F: Fn(&NaiveDateTime) -> u32 {
+    shifts.iter().fold(
         HashMap::new(),
-        |mut all, event| {
+        |mut all, shift| {
             {
                 let minutes_asleep = all
-                    .entry(current_guard)
-                    .or_insert(
-                        HashMap::new()
-                    );
-
-                match event {
-                    GuardEvent::StartOfShift { datetime, id } => {
-                        current_guard = id.clone();
-                    }
-                    GuardEvent::Sleep { datetime } => {
-                        fall_asleep_at = datetime.minute();
-                    }
-                    GuardEvent::WakeUp { datetime } => {
-                        let awake = datetime.minute();
-                        for m in fall_asleep_at..awake {
-                            let mut minute = minutes_asleep
-                                .entry(m)
-                                .or_insert(0);
-
-                            *minute += 1;
-                        }
-                    }
+                    .entry(shift.guard)
+                    .or_insert_with(HashMap::new);
+
+                for minute in shift.naps.iter().flat_map(nap_minutes) {
+                    let amount = minutes_asleep
+                        .entry(key(&minute))
+                        .or_insert(0);
+
+                    *amount += 1;
                 }
             }
             all
         })
 }
 
-pub fn sort_events(events: &mut Vec<GuardEvent>) {
-    events.sort_by(|a, b| {
-        let date1 = match a {
-            GuardEvent::StartOfShift { datetime, .. } => datetime,
-            GuardEvent::Sleep { datetime } => datetime,
-            GuardEvent::WakeUp { datetime } => datetime
-        };
+// Sleep per guard keyed by the minute of the day, 0 being midnight and 1439 being 23:59.
+pub fn minute_of_day_histogram(shifts: &[Shift]) -> GuardSleepInfo {
+    sleep_histogram(shifts, |minute| minute.hour() * 60 + minute.minute())
+}
 
-        let date2 = match b {
-            GuardEvent::StartOfShift { datetime, .. } => datetime,
-            GuardEvent::Sleep { datetime } => datetime,
-            GuardEvent::WakeUp { datetime } => datetime
-        };
+// Sleep per guard keyed by the minute of the hour, regardless of the hour it happened in.
+pub fn minute_of_hour_histogram(shifts: &[Shift]) -> GuardSleepInfo {
+    sleep_histogram(shifts, |minute| minute.minute())
+}
+
+// Expects the events to be sorted already, see `sort_events`.
+pub fn events_to_shifts(events: &[GuardEvent]) -> Result<Vec<Shift>, ShiftError> {
+    let mut shifts: Vec<Shift> = vec![];
+    let mut dates: HashSet<NaiveDate> = HashSet::new();
+    let mut asleep: Option<&GuardEvent> = None;
+
+    for event in events {
+        let line = event.to_string();
+
+        match event {
+            GuardEvent::StartOfShift { datetime, id } => {
+                if let Some(sleep) = asleep.take() {
+                    return Err(ShiftError::UnterminatedNap { line: sleep.to_string() });
+                }
+
+                let date = shift_date(datetime);
+                if !dates.insert(date) {
+                    return Err(ShiftError::DuplicateShift { line });
+                }
+
+                shifts.push(Shift { guard: *id, date, naps: vec![] });
+            }
+            GuardEvent::Sleep { .. } => {
+                if shifts.is_empty() {
+                    return Err(ShiftError::NoGuardOnDuty { line });
+                }
+                if asleep.is_some() {
+                    return Err(ShiftError::AlreadyAsleep { line });
+                }
+
+                asleep = Some(event);
+            }
+            GuardEvent::WakeUp { datetime } => {
+                let shift = match shifts.last_mut() {
+                    Some(shift) => shift,
+                    None => return Err(ShiftError::NoGuardOnDuty { line })
+                };
+                let fell_asleep = match asleep.take() {
+                    Some(sleep) => sleep.datetime(),
+                    None => return Err(ShiftError::OrphanWakeUp { line })
+                };
+
+                shift.naps.push(*fell_asleep..*datetime);
+            }
+        }
+    }
+
+    match asleep {
+        Some(sleep) => Err(ShiftError::UnterminatedNap { line: sleep.to_string() }),
+        None => Ok(shifts)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GuardSleep {
+    pub guard: u32,
+    pub total_minutes: u32,
+    pub sleepiest_minute: Option<u32>,
+    pub minute_frequency: u32,
+    pub tied_minutes: Vec<u32>,
+}
+
+impl GuardSleep {
+    fn new(guard: u32, minutes: &GuardSleepMinutes) -> GuardSleep {
+        let total_minutes = minutes.values().sum();
+        let minute_frequency = minutes.values().cloned().max().unwrap_or(0);
+
+        let mut tied_minutes: Vec<u32> = minutes
+            .iter()
+            .filter(|&(_, amount)| *amount == minute_frequency && minute_frequency > 0)
+            .map(|(minute, _)| *minute)
+            .collect();
+        tied_minutes.sort();
+
+        GuardSleep {
+            guard,
+            total_minutes,
+            sleepiest_minute: tied_minutes.first().cloned(),
+            minute_frequency,
+            tied_minutes,
+        }
+    }
+}
+
+// A heuristic for picking the guard to sneak past. Guards with a higher score are
+// considered better targets.
+pub trait SneakStrategy {
+    fn score(&self, guard: &GuardSleep) -> u32;
+}
+
+pub struct MostMinutesAsleep;
+
+impl SneakStrategy for MostMinutesAsleep {
+    fn score(&self, guard: &GuardSleep) -> u32 {
+        guard.total_minutes
+    }
+}
+
+pub struct MostFrequentMinute;
+
+impl SneakStrategy for MostFrequentMinute {
+    fn score(&self, guard: &GuardSleep) -> u32 {
+        guard.minute_frequency
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SleepReport {
+    pub guards: Vec<GuardSleep>,
+}
+
+impl SleepReport {
+    pub fn new(sleep_info: &GuardSleepInfo) -> SleepReport {
+        let mut guards: Vec<GuardSleep> = sleep_info
+            .iter()
+            .map(|(guard, minutes)| GuardSleep::new(*guard, minutes))
+            .collect();
+        guards.sort_by_key(|sleep| sleep.guard);
+
+        SleepReport { guards }
+    }
+
+    // Highest score first, ties are broken by the lowest guard id.
+    pub fn rank<S: SneakStrategy>(&self, strategy: &S) -> Vec<&GuardSleep> {
+        let mut ranked: Vec<&GuardSleep> = self.guards.iter().collect();
+        ranked.sort_by_key(|sleep| (std::cmp::Reverse(strategy.score(sleep)), sleep.guard));
+
+        ranked
+    }
+
+    // Every guard that shares the best score under the given strategy.
+    pub fn candidates<S: SneakStrategy>(&self, strategy: &S) -> Vec<&GuardSleep> {
+        let ranked = self.rank(strategy);
+        let best = ranked.first().map(|sleep| strategy.score(sleep));
 
-        date1.cmp(&date2)
-    });
+        ranked
+            .into_iter()
+            .take_while(|sleep| Some(strategy.score(sleep)) == best)
+            .collect()
+    }
+}
+
+pub fn get_most_sleeping_guard(sleep_info: &GuardSleepInfo) -> (u32, u32, u32) {
+    let report = SleepReport::new(sleep_info);
+
+    match report.rank(&MostMinutesAsleep).first() {
+        Some(sleep) => (sleep.guard, sleep.total_minutes, sleep.sleepiest_minute.unwrap_or(0)),
+        None => (0, 0, 0)
+    }
+}
+
+pub fn get_most_sleeped_minute(sleep_info: &GuardSleepInfo) -> (u32, u32, u32) {
+    let report = SleepReport::new(sleep_info);
+
+    match report.rank(&MostFrequentMinute).first() {
+        Some(sleep) => (sleep.guard, sleep.sleepiest_minute.unwrap_or(0), sleep.minute_frequency),
+        None => (0, 0, 0)
+    }
+}
+
+pub fn events_to_sleep_info(events: &[GuardEvent]) -> Result<GuardSleepInfo, ShiftError> {
+    let shifts = events_to_shifts(events)?;
+
+    Ok(minute_of_hour_histogram(&shifts))
+}
+
+pub fn sort_events(events: &mut [GuardEvent]) {
+    events.sort_by(|a, b| a.datetime().cmp(b.datetime()));
+}
+
+#[derive(Debug, Default)]
+pub struct TimelineFilter {
+    pub guard: Option<u32>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl TimelineFilter {
+    fn matches(&self, shift: &Shift) -> bool {
+        self.guard.is_none_or(|guard| guard == shift.guard) &&
+            self.from.is_none_or(|from| shift.date >= from) &&
+            self.to.is_none_or(|to| shift.date <= to)
+    }
+}
+
+// Whether the guard was asleep for each minute of the midnight hour of the shift.
+fn midnight_hour(shift: &Shift) -> Vec<bool> {
+    let mut asleep = vec![false; 60];
+
+    for minute in shift.naps.iter().flat_map(nap_minutes) {
+        if minute.date() == shift.date && minute.hour() == 0 {
+            asleep[minute.minute() as usize] = true;
+        }
+    }
+
+    asleep
+}
+
+pub fn render_timeline(shifts: &[Shift], filter: &TimelineFilter) -> String {
+    let tens: String = (0..60).map(|m| (m / 10).to_string()).collect();
+    let ones: String = (0..60).map(|m| (m % 10).to_string()).collect();
+
+    let mut lines = vec![
+        format!("{:<7}{:<7}Minute", "Date", "ID"),
+        format!("{:<14}{}", "", tens),
+        format!("{:<14}{}", "", ones),
+    ];
+
+    for shift in shifts.iter().filter(|shift| filter.matches(shift)) {
+        let minutes: String = midnight_hour(shift)
+            .into_iter()
+            .map(|asleep| if asleep { '#' } else { '.' })
+            .collect();
+
+        lines.push(format!("{:<7}{:<7}{}", shift.date.format("%m-%d").to_string(), format!("#{}", shift.guard), minutes));
+    }
+
+    lines.join("\n")
+}
+
+pub fn render_timeline_html(shifts: &[Shift], filter: &TimelineFilter) -> String {
+    let mut html = String::from("<table class=\"timeline\">\n<tr><th>Date</th><th>ID</th>");
+
+    for minute in 0..60 {
+        html.push_str(&format!("<th>{:02}</th>", minute));
+    }
+    html.push_str("</tr>\n");
+
+    for shift in shifts.iter().filter(|shift| filter.matches(shift)) {
+        html.push_str(&format!("<tr><td>{}</td><td>#{}</td>", shift.date.format("%m-%d"), shift.guard));
+
+        for asleep in midnight_hour(shift) {
+            html.push_str(if asleep { "<td class=\"asleep\">#</td>" } else { "<td class=\"awake\">.</td>" });
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+
+    html
 }
 
 impl FromStr for GuardEvent {
@@ -173,7 +402,18 @@ mod tests {
     use GuardEvent;
     use chrono::NaiveDate;
     use sort_events;
+    use events_to_shifts;
+    use Shift;
+    use ShiftError;
     use events_to_sleep_info;
+    use minute_of_day_histogram;
+    use get_most_sleeping_guard;
+    use SleepReport;
+    use MostMinutesAsleep;
+    use MostFrequentMinute;
+    use render_timeline;
+    use render_timeline_html;
+    use TimelineFilter;
 
     #[test]
     fn it_should_sort_input() {
@@ -182,16 +422,16 @@ mod tests {
         let event2: GuardEvent = "[1518-11-01 00:03] Guard #4 begins shift".parse().unwrap();
         let event3: GuardEvent = "[1518-11-01 00:02] Guard #3 begins shift".parse().unwrap();
         let event4: GuardEvent = "[1518-11-01 00:01] Guard #2 begins shift".parse().unwrap();
-        let mut events = vec![&event1, &event2, &event3, &event4];
+        let mut events = vec![event1.clone(), event2.clone(), event3.clone(), event4.clone()];
 
         // Act
         sort_events(&mut events);
 
         // Assert
-        assert_eq!(events[0], &event1);
-        assert_eq!(events[1], &event4);
-        assert_eq!(events[2], &event3);
-        assert_eq!(events[3], &event2);
+        assert_eq!(events[0], event1);
+        assert_eq!(events[1], event4);
+        assert_eq!(events[2], event3);
+        assert_eq!(events[3], event2);
     }
 
     #[test]
@@ -232,4 +472,156 @@ mod tests {
         // Assert
         assert_eq!(event, GuardEvent::WakeUp { datetime });
     }
+
+    fn parse_events(input: &str) -> Vec<GuardEvent> {
+        let mut events: Vec<GuardEvent> = input.lines().map(|l| l.trim().parse().unwrap()).collect();
+        sort_events(&mut events);
+
+        events
+    }
+
+    #[test]
+    fn it_should_group_events_into_shifts() {
+        // Arrange
+        let events = parse_events("[1518-11-01 00:05] falls asleep
+        [1518-11-01 00:25] wakes up
+        [1518-10-31 23:58] Guard #10 begins shift
+        [1518-11-01 00:30] falls asleep
+        [1518-11-01 00:55] wakes up
+        [1518-11-02 00:00] Guard #99 begins shift");
+
+        // Act
+        let shifts = events_to_shifts(&events).unwrap();
+
+        // Assert
+        assert_eq!(shifts, vec![
+            Shift {
+                guard: 10,
+                date: NaiveDate::from_ymd(1518, 11, 1),
+                naps: vec![
+                    NaiveDate::from_ymd(1518, 11, 1).and_hms(0, 5, 0)..NaiveDate::from_ymd(1518, 11, 1).and_hms(0, 25, 0),
+                    NaiveDate::from_ymd(1518, 11, 1).and_hms(0, 30, 0)..NaiveDate::from_ymd(1518, 11, 1).and_hms(0, 55, 0),
+                ],
+            },
+            Shift { guard: 99, date: NaiveDate::from_ymd(1518, 11, 2), naps: vec![] },
+        ]);
+    }
+
+    #[test]
+    fn it_should_report_invalid_event_sequences() {
+        // Arrange
+        let orphan = parse_events("[1518-11-01 00:00] Guard #10 begins shift
+        [1518-11-01 00:25] wakes up");
+        let no_guard = parse_events("[1518-11-01 00:05] falls asleep");
+        let twice = parse_events("[1518-11-01 00:00] Guard #10 begins shift
+        [1518-11-01 00:05] falls asleep
+        [1518-11-01 00:06] falls asleep");
+        let unterminated = parse_events("[1518-11-01 00:00] Guard #10 begins shift
+        [1518-11-01 00:05] falls asleep");
+        let duplicate = parse_events("[1518-10-31 23:50] Guard #10 begins shift
+        [1518-11-01 00:01] Guard #11 begins shift");
+
+        // Act & Assert
+        assert_eq!(events_to_shifts(&orphan), Err(ShiftError::OrphanWakeUp { line: "[1518-11-01 00:25] wakes up".to_string() }));
+        assert_eq!(events_to_shifts(&no_guard), Err(ShiftError::NoGuardOnDuty { line: "[1518-11-01 00:05] falls asleep".to_string() }));
+        assert_eq!(events_to_shifts(&twice), Err(ShiftError::AlreadyAsleep { line: "[1518-11-01 00:06] falls asleep".to_string() }));
+        assert_eq!(events_to_shifts(&unterminated), Err(ShiftError::UnterminatedNap { line: "[1518-11-01 00:05] falls asleep".to_string() }));
+        assert_eq!(events_to_shifts(&duplicate), Err(ShiftError::DuplicateShift { line: "[1518-11-01 00:01] Guard #11 begins shift".to_string() }));
+    }
+
+    #[test]
+    fn it_should_count_naps_across_midnight_and_hours() {
+        // Arrange
+        let events = parse_events("[1518-10-31 23:50] Guard #10 begins shift
+        [1518-10-31 23:58] falls asleep
+        [1518-11-01 00:03] wakes up
+        [1518-11-02 00:00] Guard #11 begins shift
+        [1518-11-02 00:30] falls asleep
+        [1518-11-02 01:32] wakes up");
+
+        // Act
+        let shifts = events_to_shifts(&events).unwrap();
+        let per_day = minute_of_day_histogram(&shifts);
+        let per_hour = events_to_sleep_info(&events).unwrap();
+
+        // Assert
+        assert_eq!(per_day[&10].len(), 5);
+        assert_eq!(per_day[&10][&(23 * 60 + 59)], 1);
+        assert_eq!(per_day[&10][&2], 1);
+        assert_eq!(per_hour[&10][&58], 1);
+        assert_eq!(per_hour[&11].values().sum::<u32>(), 62);
+        assert_eq!(per_hour[&11][&30], 2);
+        assert_eq!(per_hour[&11][&31], 2);
+
+        // Minutes 30 and 31 are tied, which minute wins is up to the tie-break.
+        let (guard, total, _) = get_most_sleeping_guard(&per_hour);
+        assert_eq!((guard, total), (11, 62));
+    }
+
+    #[test]
+    fn it_should_rank_guards_deterministically() {
+        // Arrange
+        let events = parse_events("[1518-11-01 00:00] Guard #20 begins shift
+        [1518-11-01 00:10] falls asleep
+        [1518-11-01 00:12] wakes up
+        [1518-11-02 00:00] Guard #10 begins shift
+        [1518-11-02 00:40] falls asleep
+        [1518-11-02 00:42] wakes up
+        [1518-11-03 00:00] Guard #30 begins shift
+        [1518-11-03 00:01] falls asleep
+        [1518-11-03 00:02] wakes up");
+        let sleep_info = events_to_sleep_info(&events).unwrap();
+
+        // Act
+        let report = SleepReport::new(&sleep_info);
+        let ranked: Vec<u32> = report.rank(&MostMinutesAsleep).iter().map(|s| s.guard).collect();
+        let candidates: Vec<u32> = report.candidates(&MostFrequentMinute).iter().map(|s| s.guard).collect();
+
+        // Assert
+        assert_eq!(ranked, vec![10, 20, 30]);
+        assert_eq!(candidates, vec![10, 20, 30]);
+        assert_eq!(report.guards[0].total_minutes, 2);
+        assert_eq!(report.guards[0].sleepiest_minute, Some(40));
+        assert_eq!(report.guards[0].tied_minutes, vec![40, 41]);
+        assert_eq!(get_most_sleeping_guard(&sleep_info), (10, 2, 40));
+    }
+
+    #[test]
+    fn it_should_render_a_timeline() {
+        // Arrange
+        let events = parse_events("[1518-11-01 00:00] Guard #10 begins shift
+        [1518-11-01 00:05] falls asleep
+        [1518-11-01 00:25] wakes up
+        [1518-11-01 00:30] falls asleep
+        [1518-11-01 00:55] wakes up
+        [1518-11-01 23:58] Guard #99 begins shift
+        [1518-11-02 00:40] falls asleep
+        [1518-11-02 00:50] wakes up
+        [1518-11-03 00:05] Guard #10 begins shift
+        [1518-11-03 00:24] falls asleep
+        [1518-11-03 00:29] wakes up");
+        let shifts = events_to_shifts(&events).unwrap();
+
+        // Act
+        let timeline = render_timeline(&shifts, &TimelineFilter::default());
+        let filtered = render_timeline(&shifts, &TimelineFilter {
+            guard: Some(10),
+            from: Some(NaiveDate::from_ymd(1518, 11, 2)),
+            to: None,
+        });
+        let html = render_timeline_html(&shifts, &TimelineFilter { guard: Some(99), ..TimelineFilter::default() });
+
+        // Assert
+        assert_eq!(timeline, "\
+Date   ID     Minute
+              000000000011111111112222222222333333333344444444445555555555
+              012345678901234567890123456789012345678901234567890123456789
+11-01  #10    .....####################.....#########################.....
+11-02  #99    ........................................##########..........
+11-03  #10    ........................#####...............................");
+        assert_eq!(filtered.lines().count(), 4);
+        assert!(filtered.ends_with("11-03  #10    ........................#####..............................."));
+        assert_eq!(html.matches("<tr>").count(), 2);
+        assert_eq!(html.matches("class=\"asleep\"").count(), 10);
+    }
 }
@@ -1,21 +1,323 @@
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::str::FromStr;
 
-pub fn react(input: String) -> usize {
-    let mut new_polymer = Vec::new();
-    for c in input.chars() {
-        match new_polymer.last() {
-            None => new_polymer.push(c),
-            Some(l) => {
-                if is_opposite(c, *l) {
-                    new_polymer.pop();
-                } else {
-                    new_polymer.push(c);
+// A single unit of a polymer: a byte for ASCII polymers, so even huge ones stay compact,
+// or a char for everything else.
+pub trait Unit: Copy {
+    fn is_whitespace(self) -> bool;
+}
+
+impl Unit for u8 {
+    fn is_whitespace(self) -> bool {
+        self.is_ascii_whitespace()
+    }
+}
+
+impl Unit for char {
+    fn is_whitespace(self) -> bool {
+        self.is_ascii_whitespace()
+    }
+}
+
+// Decides which pairs of units annihilate each other, and which units belong to the same
+// unit type when a type is removed from the polymer altogether.
+pub trait ReactionRules<U: Unit = char> {
+    fn reacts(&self, unit1: U, unit2: U) -> bool;
+    fn unit_type(&self, unit: U) -> U;
+}
+
+impl<U: Unit, R: ReactionRules<U>> ReactionRules<U> for &R {
+    fn reacts(&self, unit1: U, unit2: U) -> bool {
+        (*self).reacts(unit1, unit2)
+    }
+
+    fn unit_type(&self, unit: U) -> U {
+        (*self).unit_type(unit)
+    }
+}
+
+// The rule from the puzzle: the same ASCII letter in opposite case.
+#[derive(Debug, Default)]
+pub struct AsciiCaseRule;
+
+impl ReactionRules for AsciiCaseRule {
+    fn reacts(&self, unit1: char, unit2: char) -> bool {
+        is_opposite(unit1, unit2)
+    }
+
+    fn unit_type(&self, unit: char) -> char {
+        unit.to_ascii_lowercase()
+    }
+}
+
+impl ReactionRules<u8> for AsciiCaseRule {
+    fn reacts(&self, unit1: u8, unit2: u8) -> bool {
+        unit1 != unit2 && unit1.eq_ignore_ascii_case(&unit2)
+    }
+
+    fn unit_type(&self, unit: u8) -> u8 {
+        unit.to_ascii_lowercase()
+    }
+}
+
+pub struct UnicodeCaseRule;
+
+impl ReactionRules for UnicodeCaseRule {
+    fn reacts(&self, unit1: char, unit2: char) -> bool {
+        unit1 != unit2 && unit1.to_lowercase().eq(unit2.to_lowercase())
+    }
+
+    fn unit_type(&self, unit: char) -> char {
+        unit.to_lowercase().next().unwrap_or(unit)
+    }
+}
+
+// Units that react with each other are of the same unit type, also when they are only
+// linked through other pairs. The types are kept as a union-find, with the lowest unit of
+// every type as its root.
+#[derive(Debug, Default)]
+pub struct PairTable {
+    pairs: HashSet<(char, char)>,
+    parents: HashMap<char, char>,
+}
+
+impl PairTable {
+    pub fn new() -> PairTable {
+        PairTable::default()
+    }
+
+    pub fn add_pair(&mut self, unit1: char, unit2: char) {
+        self.pairs.insert((unit1, unit2));
+        self.pairs.insert((unit2, unit1));
+
+        let root1 = self.unit_type(unit1);
+        let root2 = self.unit_type(unit2);
+        if root1 != root2 {
+            self.parents.insert(root1.max(root2), root1.min(root2));
+        }
+    }
+}
+
+impl ReactionRules for PairTable {
+    fn reacts(&self, unit1: char, unit2: char) -> bool {
+        self.pairs.contains(&(unit1, unit2))
+    }
+
+    fn unit_type(&self, unit: char) -> char {
+        let mut unit = unit;
+        while let Some(&parent) = self.parents.get(&unit) {
+            unit = parent;
+        }
+
+        unit
+    }
+}
+
+// Rules as read from a rules file, one per line: `ascii-case`, `unicode-case` or a pair
+// of units separated by whitespace. Blank lines and lines starting with `#` are ignored.
+//
+// A pair links the case folded units in `types`, so a unit type holds both cases of every
+// letter that reacts with another letter.
+#[derive(Debug, Default)]
+pub struct RuleSet {
+    ascii_case: bool,
+    unicode_case: bool,
+    pairs: PairTable,
+    types: PairTable,
+}
+
+impl RuleSet {
+    fn fold_case(&self, unit: char) -> char {
+        if self.unicode_case {
+            UnicodeCaseRule.unit_type(unit)
+        } else if self.ascii_case {
+            AsciiCaseRule.unit_type(unit)
+        } else {
+            unit
+        }
+    }
+}
+
+impl ReactionRules for RuleSet {
+    fn reacts(&self, unit1: char, unit2: char) -> bool {
+        (self.ascii_case && AsciiCaseRule.reacts(unit1, unit2)) ||
+            (self.unicode_case && UnicodeCaseRule.reacts(unit1, unit2)) ||
+            self.pairs.reacts(unit1, unit2)
+    }
+
+    fn unit_type(&self, unit: char) -> char {
+        self.types.unit_type(self.fold_case(unit))
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = String;
+    fn from_str(input: &str) -> Result<RuleSet, String> {
+        let mut rules = RuleSet::default();
+        let mut pairs = vec![];
+
+        for (number, line) in input.lines().enumerate() {
+            let line = line.trim();
+            let units: Vec<char> = line.split_whitespace().filter_map(|unit| {
+                let mut chars = unit.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => None
                 }
+            }).collect();
+
+            match line {
+                "" => {}
+                _ if line.starts_with('#') => {}
+                "ascii-case" => rules.ascii_case = true,
+                "unicode-case" => rules.unicode_case = true,
+                _ if units.len() == 2 && line.split_whitespace().count() == 2 => pairs.push((units[0], units[1])),
+                _ => return Err(format!("invalid rule on line {}: {}", number + 1, line))
+            }
+        }
+
+        // The case rules can come after the pairs, so the pairs are only folded at the end.
+        for (unit1, unit2) in pairs {
+            rules.pairs.add_pair(unit1, unit2);
+
+            let (type1, type2) = (rules.fold_case(unit1), rules.fold_case(unit2));
+            rules.types.add_pair(type1, type2);
+        }
+
+        Ok(rules)
+    }
+}
+
+// Keeps the units that survived so far on a stack, so every new unit only has to be
+// checked against the top of it. Whitespace, such as the trailing newline of an input
+// file, is not a unit and is skipped.
+#[derive(Debug, Default)]
+pub struct Reactor<U: Unit = u8, R: ReactionRules<U> = AsciiCaseRule> {
+    rules: R,
+    stack: Vec<U>,
+}
+
+impl Reactor {
+    pub fn new() -> Reactor {
+        Reactor::default()
+    }
+}
+
+impl<U: Unit, R: ReactionRules<U>> Reactor<U, R> {
+    pub fn with_rules(rules: R) -> Reactor<U, R> {
+        Reactor { rules, stack: vec![] }
+    }
+
+    pub fn push(&mut self, unit: U) {
+        if unit.is_whitespace() {
+            return;
+        }
+
+        match self.stack.last() {
+            Some(&last) if self.rules.reacts(last, unit) => {
+                self.stack.pop();
             }
+            _ => self.stack.push(unit)
         }
     }
 
-    new_polymer.len()
+    pub fn extend<I: IntoIterator<Item = U>>(&mut self, units: I) {
+        units.into_iter().for_each(|unit| self.push(unit));
+    }
+
+    pub fn polymer(&self) -> &[U] {
+        &self.stack
+    }
+
+    pub fn into_polymer(self) -> Vec<U> {
+        self.stack
+    }
+}
+
+pub fn react(input: String) -> usize {
+    let mut reactor = Reactor::with_rules(AsciiCaseRule);
+    reactor.extend(input.chars());
+
+    reactor.polymer().len()
+}
+
+pub fn react_bytes(polymer: &[u8]) -> Vec<u8> {
+    let mut reactor = Reactor::new();
+    reactor.extend(polymer.iter().cloned());
+
+    reactor.into_polymer()
+}
+
+// Reads the polymer in chunks, so it never has to be in memory as a whole.
+pub fn react_stream<R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+    let mut reactor = Reactor::new();
+    let mut buffer = [0; 64 * 1024];
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        };
+
+        reactor.extend(buffer[..read].iter().cloned());
+    }
+
+    Ok(reactor.into_polymer())
+}
+
+// A reduced polymer never contains two adjacent units that react, so when two of them
+// are joined only the units around the seam can still annihilate each other.
+pub fn merge_reduced(mut left: Vec<u8>, right: Vec<u8>) -> Vec<u8> {
+    let mut skip = 0;
+
+    while skip < right.len() {
+        match left.last() {
+            Some(&last) if AsciiCaseRule.reacts(last, right[skip]) => {
+                left.pop();
+                skip += 1;
+            }
+            _ => break
+        }
+    }
+
+    left.extend_from_slice(&right[skip..]);
+
+    left
+}
+
+pub fn react_parallel(polymer: &[u8], chunk_size: usize) -> Vec<u8> {
+    polymer
+        .par_chunks(chunk_size.max(1))
+        .map(react_bytes)
+        .reduce(Vec::new, merge_reduced)
+}
+
+pub fn react_with<R: ReactionRules>(input: &str, rules: &R) -> String {
+    let mut reactor = Reactor::with_rules(rules);
+    reactor.extend(input.chars());
+
+    reactor.into_polymer().into_iter().collect()
+}
+
+// With arbitrary pairs the result can depend on which units react first, so unlike
+// `get_removal_report` every removal starts again from the original polymer.
+pub fn find_most_blocking_unit_with<R: ReactionRules + Sync>(string: &str, rules: &R) -> usize {
+    let unit_types: HashSet<char> = string.chars().map(|c| rules.unit_type(c)).collect();
+
+    unit_types
+        .into_par_iter()
+        .map(|unit_type| {
+            let mut reactor = Reactor::with_rules(rules);
+            reactor.extend(string.chars().filter(|&c| rules.unit_type(c) != unit_type));
+
+            reactor.polymer().len()
+        })
+        .min()
+        .unwrap_or(0)
 }
 
 pub fn is_opposite(char1: char, char2: char) -> bool {
@@ -32,22 +334,62 @@ pub fn remove_char_from_string(string: &str, char1: &char, char2: &char) -> Stri
 const LOWERCASE_CHARS: [char; 26] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z'];
 const UPPERCASE_CHARS: [char; 26] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z'];
 
-pub fn find_most_blocking_unit(string: &str) -> usize {
-    LOWERCASE_CHARS
+#[derive(Debug, PartialEq)]
+pub struct UnitRemoval {
+    pub unit: char,
+    pub length: usize,
+}
+
+// Every unit type with the length of the polymer after removing it, shortest first.
+#[derive(Debug, PartialEq)]
+pub struct RemovalReport {
+    pub removals: Vec<UnitRemoval>,
+}
+
+impl RemovalReport {
+    pub fn best(&self) -> Option<&UnitRemoval> {
+        self.removals.first()
+    }
+}
+
+// Units only ever react with their own type, so removing a type from the reduced polymer
+// gives the same result as removing it from the original one, at a fraction of the work.
+pub fn get_removal_report(string: &str) -> RemovalReport {
+    let mut reactor = Reactor::with_rules(AsciiCaseRule);
+    reactor.extend(string.chars());
+    let reduced = reactor.into_polymer();
+
+    let mut removals: Vec<UnitRemoval> = LOWERCASE_CHARS
         .par_iter()
         .zip(UPPERCASE_CHARS.par_iter())
         .map(|(l, u)| {
-            let string = remove_char_from_string(string, l, u);
-            react(string)
+            let mut reactor = Reactor::with_rules(AsciiCaseRule);
+            reactor.extend(reduced.iter().cloned().filter(|unit| unit != l && unit != u));
+
+            UnitRemoval { unit: *l, length: reactor.polymer().len() }
         })
-        .min()
-        .unwrap()
+        .collect();
+    removals.sort_by_key(|removal| (removal.length, removal.unit));
+
+    RemovalReport { removals }
+}
+
+pub fn find_most_blocking_unit(string: &str) -> usize {
+    get_removal_report(string).best().unwrap().length
 }
 
 #[cfg(test)]
 mod tests {
     use crate::remove_char_from_string;
     use crate::react;
+    use crate::react_bytes;
+    use crate::react_stream;
+    use crate::react_parallel;
+    use crate::react_with;
+    use crate::find_most_blocking_unit;
+    use crate::{get_removal_report, UnitRemoval};
+    use crate::find_most_blocking_unit_with;
+    use crate::{AsciiCaseRule, UnicodeCaseRule, RuleSet, ReactionRules};
 
     #[test]
     fn it_should_remove_chars_from_string() {
@@ -129,4 +471,152 @@ mod tests {
         // Assert
         assert_eq!(result, 10);
     }
+
+    #[test]
+    fn it_should_return_the_reduced_polymer() {
+
+        // Arrange
+        let input = b"dabAcCaCBAcCcaDA";
+
+        // Act
+        let result = react_bytes(input);
+
+        // Assert
+        assert_eq!(result, b"dabCBAcaDA".to_vec());
+    }
+
+    #[test]
+    fn it_should_react_a_stream() {
+
+        // Arrange
+        let input = "dabAcCaCBAcCcaDA\n".as_bytes();
+
+        // Act
+        let result = react_stream(input).unwrap();
+
+        // Assert
+        assert_eq!(result, b"dabCBAcaDA".to_vec());
+    }
+
+    #[test]
+    fn it_should_skip_whitespace_and_count_units() {
+
+        // Arrange
+        let input = "dabAcCaCBAcCcaDA\n";
+
+        // Act
+        let result = react(input.to_string());
+        let bytes = react_bytes(input.as_bytes());
+        let stream = react_stream(input.as_bytes()).unwrap();
+
+        // Assert
+        assert_eq!(result, 10);
+        assert_eq!(bytes, stream);
+        assert_eq!(react_parallel(input.as_bytes(), 4), stream);
+        assert_eq!(react(String::from("éaAÉ\n")), 2);
+    }
+
+    #[test]
+    fn it_should_react_chunks_in_parallel() {
+
+        // Arrange
+        let mut seed: u32 = 42;
+        let input: Vec<u8> = (0..10_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                b"aAbBcC"[(seed >> 16) as usize % 6]
+            })
+            .collect();
+
+        // Act
+        let results: Vec<Vec<u8>> = [1, 7, 64, 10_000, 20_000]
+            .iter()
+            .map(|&chunk_size| react_parallel(&input, chunk_size))
+            .collect();
+
+        // Assert
+        for result in results {
+            assert_eq!(result, react_bytes(&input));
+        }
+        assert_eq!(react_parallel(b"dabAcCaCBAcCcaDA", 3), b"dabCBAcaDA".to_vec());
+    }
+
+    #[test]
+    fn it_should_react_with_pluggable_rules() {
+
+        // Arrange
+        let input = "dabAcCaCBAcCcaDA";
+        let rules: RuleSet = "# opposite case and x with y
+        ascii-case
+        x y".parse().unwrap();
+
+        // Act
+        let ascii = react_with(input, &AsciiCaseRule);
+        let unicode = react_with("éÉaΣσB", &UnicodeCaseRule);
+        let pairs = react_with("axyAbyxB", &rules);
+
+        // Assert
+        assert_eq!(ascii, "dabCBAcaDA");
+        assert_eq!(unicode, "aB");
+        assert_eq!(pairs, "");
+        assert_eq!(find_most_blocking_unit_with(input, &AsciiCaseRule), find_most_blocking_unit(input));
+        assert_eq!(find_most_blocking_unit_with("xaAyzZy", &rules), 0);
+        assert!("ascii-case\nfoo bar".parse::<RuleSet>().is_err());
+    }
+
+    #[test]
+    fn it_should_merge_chained_pairs_into_one_unit_type() {
+
+        // Arrange
+        let rules: RuleSet = "x y
+        a x".parse().unwrap();
+
+        // Act
+        let result = find_most_blocking_unit_with("paaaqyyyr", &rules);
+
+        // Assert
+        assert_eq!(rules.unit_type('y'), 'a');
+        assert_eq!(rules.unit_type('x'), 'a');
+        assert_eq!(rules.unit_type('p'), 'p');
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    fn it_should_merge_case_rules_and_pairs_into_one_unit_type() {
+
+        // Arrange
+        let rules: RuleSet = "A b
+        ascii-case".parse().unwrap();
+
+        // Act
+        let result = find_most_blocking_unit_with("BcbdBeb", &rules);
+
+        // Assert
+        for unit in "aAbB".chars() {
+            assert_eq!(rules.unit_type(unit), 'a');
+        }
+        assert!(rules.reacts('A', 'b'));
+        assert!(!rules.reacts('a', 'b'));
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    fn it_should_report_the_impact_of_every_unit() {
+
+        // Arrange
+        let input = "dabAcCaCBAcCcaDA";
+
+        // Act
+        let result = get_removal_report(input);
+
+        // Assert
+        assert_eq!(result.removals.len(), 26);
+        assert_eq!(result.best(), Some(&UnitRemoval { unit: 'c', length: 4 }));
+        assert_eq!(&result.removals[1..4], &[
+            UnitRemoval { unit: 'a', length: 6 },
+            UnitRemoval { unit: 'd', length: 6 },
+            UnitRemoval { unit: 'b', length: 8 },
+        ]);
+        assert_eq!(find_most_blocking_unit(input), 4);
+    }
 }
@@ -2,29 +2,43 @@ use std::str::FromStr;
 use regex::Regex;
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::sync::atomic::{self, AtomicUsize};
 use std::collections::HashSet;
+use std::collections::VecDeque;
 
-extern crate uuid;
 extern crate regex;
 
-static OBJECT_COUNTER: AtomicUsize = atomic::ATOMIC_USIZE_INIT;
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Point {
-    pub id: usize,
     pub x: i32,
     pub y: i32,
 }
 
 impl Point {
-    fn new(x: i32, y: i32) -> Point {
-        let id = OBJECT_COUNTER.fetch_add(1, atomic::Ordering::SeqCst);
-
-        Point { x, y, id }
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
     }
 }
 
+// A labelled location from the input. Its id is its position in the input, so it does
+// not depend on anything else that happens to be created in the meantime.
+#[derive(Debug, PartialEq)]
+pub struct Site {
+    pub id: usize,
+    pub point: Point,
+}
+
+pub fn to_sites(points: Vec<Point>) -> Vec<Site> {
+    points
+        .into_iter()
+        .enumerate()
+        .map(|(id, point)| Site { id, point })
+        .collect()
+}
+
+pub fn parse_sites(input: &str) -> Vec<Site> {
+    to_sites(input.lines().map(|line| line.parse().unwrap()).collect())
+}
+
 impl FromStr for Point {
     type Err = ();
     fn from_str(input: &str) -> Result<Point, ()> {
@@ -41,89 +55,356 @@ impl FromStr for Point {
     }
 }
 
-pub type Area = Vec<Vec<Option<usize>>>;
-
-pub fn get_infinite_areas(areas: &Area) -> HashSet<&usize> {
-    let mut edges = HashSet::new();
+// Row `y` of the cells holds the locations at `origin.y + y`, column `x` the locations
+// at `origin.x + x`.
+#[derive(Debug, PartialEq)]
+pub struct Area {
+    pub origin: Point,
+    pub cells: Vec<Vec<Option<usize>>>,
+}
 
-    // First row
-    areas
-        .first()
-        .unwrap()
-        .iter()
-        .for_each(|p| {
-            match p {
-                Some(id) => {
-                    edges.insert(id);
+impl Area {
+    fn edge_ids(&self) -> HashSet<usize> {
+        let mut edges = HashSet::new();
+
+        // First and last row
+        self.cells.first().into_iter()
+            .chain(self.cells.last())
+            .flat_map(|row| row.iter())
+            .for_each(|owner| {
+                if let Some(id) = owner {
+                    edges.insert(*id);
                 }
-                None => {}
-            };
-        });
+            });
 
-    // lLast row
-    areas
-        .last()
-        .unwrap()
-        .iter()
-        .for_each(|p| {
-            match p {
-                Some(id) => {
-                    edges.insert(id);
-                }
-                None => {}
-            };
+        // Row edges
+        self.cells.iter().for_each(|row| {
+            if let Some(Some(id)) = row.first() {
+                edges.insert(*id);
+            }
+            if let Some(Some(id)) = row.last() {
+                edges.insert(*id);
+            }
         });
 
-    // Row edges
-    areas.iter().for_each(|row| {
-        match row.first().unwrap() {
-            Some(id) => {
-                edges.insert(id);
+        edges
+    }
+}
+
+pub trait Metric {
+    fn distance(&self, point: &Point, point2: &Point) -> i64;
+
+    // Steps for which the breadth first distance over the grid equals this metric, if any.
+    fn flood_steps(&self) -> Option<&'static [(i32, i32)]> {
+        None
+    }
+
+    // Ids of the sites that are the single nearest site for locations arbitrarily far away.
+    fn infinite_sites(&self, sites: &[Site]) -> HashSet<usize>;
+
+    // Top left and bottom right corner of a box containing every finite area, if the
+    // metric knows one without labelling the grid.
+    fn finite_extent(&self, _sites: &[Site]) -> Option<(Point, Point)> {
+        None
+    }
+}
+
+pub struct Manhattan;
+pub struct Chebyshev;
+pub struct SquaredEuclidean;
+
+const ORTHOGONAL_STEPS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const ALL_STEPS: [(i32, i32); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+// The id of the site with the lowest key, unless several sites share it.
+fn unique_minimum<F: Fn(&Point) -> i64>(sites: &[Site], key: F) -> Option<usize> {
+    let mut keys: Vec<(i64, usize)> = sites.iter().map(|site| (key(&site.point), site.id)).collect();
+    keys.sort();
+
+    match (keys.first(), keys.get(1)) {
+        (Some(first), Some(second)) if first.0 == second.0 => None,
+        (Some(first), _) => Some(first.1),
+        _ => None
+    }
+}
+
+impl Metric for Manhattan {
+    fn distance(&self, point: &Point, point2: &Point) -> i64 {
+        get_distance(point, point2) as i64
+    }
+
+    fn flood_steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&ORTHOGONAL_STEPS)
+    }
+
+    // Walking straight away from the bounding box adds the same distance to every site, so
+    // whoever owns a location on its edge owns everything beyond it.
+    fn infinite_sites(&self, sites: &[Site]) -> HashSet<usize> {
+        let (min, max) = get_bounding_box(sites);
+        let mut infinite = HashSet::new();
+
+        for x in min.x..=max.x {
+            let x = x as i64;
+            infinite.extend(unique_minimum(sites, |p| (x - p.x as i64).abs() + p.y as i64));
+            infinite.extend(unique_minimum(sites, |p| (x - p.x as i64).abs() - p.y as i64));
+        }
+        for y in min.y..=max.y {
+            let y = y as i64;
+            infinite.extend(unique_minimum(sites, |p| (y - p.y as i64).abs() + p.x as i64));
+            infinite.extend(unique_minimum(sites, |p| (y - p.y as i64).abs() - p.x as i64));
+        }
+
+        infinite
+    }
+}
+
+impl Metric for Chebyshev {
+    fn distance(&self, point: &Point, point2: &Point) -> i64 {
+        ((point.x - point2.x).abs().max((point.y - point2.y).abs())) as i64
+    }
+
+    fn flood_steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&ALL_STEPS)
+    }
+
+    // Far away along an axis only the sites furthest in that direction can be nearest.
+    // Far away along a diagonal the distance to a site grows as `k + max(c + a, b)`,
+    // where `a` and `b` are the site's coordinates projected on the diagonal's axes and
+    // `c` depends on where the diagonal started, so every start has to be tried once.
+    fn infinite_sites(&self, sites: &[Site]) -> HashSet<usize> {
+        let mut infinite = HashSet::new();
+
+        infinite.extend(unique_minimum(sites, |p| p.x as i64));
+        infinite.extend(unique_minimum(sites, |p| -p.x as i64));
+        infinite.extend(unique_minimum(sites, |p| p.y as i64));
+        infinite.extend(unique_minimum(sites, |p| -p.y as i64));
+
+        for &(dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)].iter() {
+            let offsets: Vec<i64> = sites
+                .iter()
+                .map(|site| (-dy * site.point.y + dx * site.point.x) as i64)
+                .collect();
+            let low = offsets.iter().cloned().min().unwrap_or(0) - 1;
+            let high = offsets.iter().cloned().max().unwrap_or(0) + 1;
+
+            for c in low..=high {
+                infinite.extend(unique_minimum(sites, |p| {
+                    (c - (dx * p.x) as i64).max(-(dy * p.y) as i64)
+                }));
             }
-            None => {}
+        }
+
+        infinite
+    }
+}
+
+impl Metric for SquaredEuclidean {
+    fn distance(&self, point: &Point, point2: &Point) -> i64 {
+        let dx = (point.x - point2.x) as i64;
+        let dy = (point.y - point2.y) as i64;
+
+        dx * dx + dy * dy
+    }
+
+    // A site is nearest to everything along a ray leaving it in a direction no other site
+    // lies ahead of, which exists exactly when the site is on the convex hull.
+    fn infinite_sites(&self, sites: &[Site]) -> HashSet<usize> {
+        let cross = |o: &Point, a: &Point, b: &Point| {
+            (a.x - o.x) as i64 * (b.y - o.y) as i64 - (a.y - o.y) as i64 * (b.x - o.x) as i64
         };
-        match row.last().unwrap() {
-            Some(id) => {
-                edges.insert(id);
+
+        sites
+            .iter()
+            .filter(|site| sites.iter().filter(|other| other.point == site.point).count() == 1)
+            .filter(|site| {
+                let others: Vec<&Point> = sites.iter().map(|other| &other.point).filter(|p| **p != site.point).collect();
+
+                others.is_empty() || others.iter().any(|a| {
+                    others.iter().all(|b| cross(&site.point, a, b) >= 0) ||
+                        others.iter().all(|b| cross(&site.point, a, b) <= 0)
+                })
+            })
+            .map(|site| site.id)
+            .collect()
+    }
+
+    // A finite area is a convex polygon with Voronoi vertices for corners. Those are the
+    // centres of the circles through three sites that have no other site inside them.
+    fn finite_extent(&self, sites: &[Site]) -> Option<(Point, Point)> {
+        let (mut min, mut max) = get_bounding_box(sites);
+
+        for (i, a) in sites.iter().enumerate() {
+            for (j, b) in sites.iter().enumerate().skip(i + 1) {
+                for c in sites.iter().skip(j + 1) {
+                    let centre = match circumcentre(&a.point, &b.point, &c.point) {
+                        Some(centre) => centre,
+                        None => continue
+                    };
+                    let radius = squared_distance(centre, &a.point);
+                    let empty = sites
+                        .iter()
+                        .all(|site| squared_distance(centre, &site.point) >= radius * (1.0 - 1e-9));
+
+                    if empty {
+                        min = Point::new(min.x.min(centre.0.floor() as i32), min.y.min(centre.1.floor() as i32));
+                        max = Point::new(max.x.max(centre.0.ceil() as i32), max.y.max(centre.1.ceil() as i32));
+                    }
+                }
             }
-            None => {}
-        };
-    });
+        }
 
-    edges
+        Some((min, max))
+    }
+}
+
+fn squared_distance(centre: (f64, f64), point: &Point) -> f64 {
+    let dx = centre.0 - point.x as f64;
+    let dy = centre.1 - point.y as f64;
+
+    dx * dx + dy * dy
+}
+
+// Sites on one line have no circle through them.
+fn circumcentre(a: &Point, b: &Point, c: &Point) -> Option<(f64, f64)> {
+    let (ax, ay, bx, by, cx, cy) = (a.x as i64, a.y as i64, b.x as i64, b.y as i64, c.x as i64, c.y as i64);
+    let d = 2 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    if d == 0 {
+        return None;
+    }
+
+    let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+    let x = (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) as f64 / d as f64;
+    let y = (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) as f64 / d as f64;
+
+    Some((x, y))
+}
+
+pub fn get_infinite_areas<M: Metric>(sites: &[Site], metric: &M) -> HashSet<usize> {
+    metric.infinite_sites(sites)
 }
 
 pub fn print_area(area: &Area) {
-    for x in area {
-        for y in x {
-            match y {
+    for row in &area.cells {
+        for cell in row {
+            match cell {
                 Some(id) => {
                     print!("{}", id);
                 }
                 None => print!("x")
             }
         }
-        println!("");
+        println!();
     }
 }
 
-pub fn count_areas(area: &Area) -> HashMap<&usize, i32> {
+#[derive(Debug)]
+pub struct SvgOptions {
+    pub cell_size: u32,
+    pub safe_threshold: Option<i64>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions { cell_size: 4, safe_threshold: None }
+    }
+}
+
+fn site_colour(id: usize) -> String {
+    format!("hsl({}, 65%, 60%)", (id * 137) % 360)
+}
+
+// Splits a row into runs of cells that share the same value, as (start, length, value).
+fn runs<T: PartialEq + Copy>(row: &[T]) -> Vec<(usize, usize, T)> {
+    let mut runs: Vec<(usize, usize, T)> = vec![];
+
+    for (x, &value) in row.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if run.2 == value => run.1 += 1,
+            _ => runs.push((x, 1, value))
+        }
+    }
+
+    runs
+}
+
+// Draws the area as an SVG image: every site gets its own colour, ties are grey, infinite
+// areas are hatched and every site is marked with a dot. When a threshold is given the
+// locations whose summed distance is below it are outlined as well.
+pub fn render_svg<M: Metric>(area: &Area, sites: &[Site], metric: &M, options: &SvgOptions) -> String {
+    let size = options.cell_size as usize;
+    let infinite = get_infinite_areas(sites, metric);
+    let width = area.cells.first().map_or(0, |row| row.len());
+    let height = area.cells.len();
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        width * size,
+        height * size
+    );
+    svg.push_str("<defs><pattern id=\"hatch\" width=\"4\" height=\"4\" patternUnits=\"userSpaceOnUse\">");
+    svg.push_str("<path d=\"M0,4 L4,0\" stroke=\"black\" stroke-opacity=\"0.4\"/></pattern></defs>\n");
+
+    for (y, row) in area.cells.iter().enumerate() {
+        for (x, length, owner) in runs(row) {
+            let fill = owner.map_or("#999".to_string(), site_colour);
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                x * size, y * size, length * size, size, fill
+            ));
+
+            if owner.is_some_and(|id| infinite.contains(&id)) {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"url(#hatch)\"/>\n",
+                    x * size, y * size, length * size, size
+                ));
+            }
+        }
+    }
+
+    if let Some(threshold) = options.safe_threshold {
+        for (y, row) in area.cells.iter().enumerate() {
+            let safe: Vec<bool> = (0..row.len())
+                .map(|x| {
+                    let point = Point::new(area.origin.x + x as i32, area.origin.y + y as i32);
+                    get_sum_of_distances(sites, &point, metric) < threshold
+                })
+                .collect();
+
+            for (x, length, _) in runs(&safe).into_iter().filter(|run| run.2) {
+                svg.push_str(&format!(
+                    "<rect class=\"safe\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" fill-opacity=\"0.5\"/>\n",
+                    x * size, y * size, length * size, size
+                ));
+            }
+        }
+    }
+
+    for site in sites {
+        svg.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"black\"><title>{}</title></circle>\n",
+            (site.point.x - area.origin.x) as usize * size + size / 2,
+            (site.point.y - area.origin.y) as usize * size + size / 2,
+            (size / 2).max(1),
+            site.id
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+pub fn count_areas<M: Metric>(area: &Area, sites: &[Site], metric: &M) -> HashMap<usize, i32> {
     let mut map = HashMap::new();
-    let infinite_areas = get_infinite_areas(&area);
-    area
+    let infinite_areas = get_infinite_areas(sites, metric);
+    area.cells
         .iter()
-        .for_each(|vec| {
-            vec
-                .iter()
-                .for_each(|opt| {
-                    match opt {
-                        Some(id) => {
-                            let amount = map.entry(id).or_insert(0);
-                            *amount += 1;
-                        }
-                        None => {}
-                    }
-                });
+        .flat_map(|row| row.iter())
+        .for_each(|cell| {
+            if let Some(id) = cell {
+                let amount = map.entry(*id).or_insert(0);
+                *amount += 1;
+            }
         });
 
     map
@@ -133,21 +414,20 @@ pub fn count_areas(area: &Area) -> HashMap<&usize, i32> {
         }).collect()
 }
 
-pub fn get_sum_of_distances(points: &Vec<Point>, point: &Point) -> i32 {
-    points
+pub fn get_sum_of_distances<M: Metric>(sites: &[Site], point: &Point, metric: &M) -> i64 {
+    sites
         .iter()
-        .map(|p| get_distance(p, point))
+        .map(|site| metric.distance(&site.point, point))
         .sum()
 }
 
-pub fn get_area_of_distances(points: &Vec<Point>) -> Vec<i32> {
-    let (width, height) = get_width_height(points);
+pub fn get_area_of_distances<M: Metric>(sites: &[Site], metric: &M) -> Vec<i64> {
+    let (min, max) = get_bounding_box(sites);
 
     let mut region = Vec::new();
-    for y in 0..height + 1 {
-        for x in 0..width + 2 {
-            let point = &Point::new(x, y);
-            let distance_sum = get_sum_of_distances(points, point);
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let distance_sum = get_sum_of_distances(sites, &Point::new(x, y), metric);
             region.push(distance_sum);
         }
     }
@@ -155,43 +435,206 @@ pub fn get_area_of_distances(points: &Vec<Point>) -> Vec<i32> {
     region
 }
 
-fn get_width_height(points: &Vec<Point>) -> (i32, i32) {
-    points
-        .iter()
-        .fold(
-            (0, 0),
-            |(width, height), p| (width.max(p.x), height.max(p.y)),
-        )
+#[derive(Debug, PartialEq)]
+pub struct Region {
+    pub size: u64,
+    pub extent: Option<(Point, Point)>,
+    pub connected: bool,
+}
+
+// Summed distances to every coordinate on one axis, for each coordinate in the range that
+// can still be below the threshold. Past the outermost site every step adds one per site.
+fn axis_distances(coordinates: &[i32], threshold: i64) -> Vec<(i32, i64)> {
+    let min = coordinates.iter().cloned().min().unwrap_or(0);
+    let max = coordinates.iter().cloned().max().unwrap_or(0);
+    let reach = (threshold / coordinates.len().max(1) as i64 + 1) as i32;
+
+    (min - reach..=max + reach)
+        .map(|c| (c, coordinates.iter().map(|&o| (c - o).abs() as i64).sum()))
+        .filter(|&(_, sum)| sum < threshold)
+        .collect()
+}
+
+// Manhattan distance splits into a horizontal and a vertical part, so the summed distance
+// of a location is the summed distance of its column plus that of its row. Both parts are
+// convex, which means every column in the region contains the row with the lowest sum and
+// the region is always one connected piece.
+//
+// Without any sites every location is safe and the region never ends, which a `Region`
+// can't describe, so an empty region is returned instead.
+pub fn safe_region(sites: &[Site], threshold: i64) -> Region {
+    if sites.is_empty() {
+        return Region { size: 0, extent: None, connected: false };
+    }
+
+    let xs: Vec<i32> = sites.iter().map(|site| site.point.x).collect();
+    let ys: Vec<i32> = sites.iter().map(|site| site.point.y).collect();
+    let columns = axis_distances(&xs, threshold);
+    let rows = axis_distances(&ys, threshold);
+
+    let mut row_sums: Vec<i64> = rows.iter().map(|&(_, sum)| sum).collect();
+    row_sums.sort();
+
+    let mut size = 0;
+    let mut extent: Option<(Point, Point)> = None;
+
+    for &(x, column_sum) in &columns {
+        let in_region = row_sums.iter().take_while(|&&row_sum| column_sum + row_sum < threshold).count();
+        if in_region == 0 {
+            continue;
+        }
+        size += in_region as u64;
+
+        let ys_in_region = rows.iter().filter(|&&(_, row_sum)| column_sum + row_sum < threshold).map(|&(y, _)| y);
+        let top = ys_in_region.clone().min().unwrap();
+        let bottom = ys_in_region.max().unwrap();
+
+        extent = Some(match extent {
+            None => (Point::new(x, top), Point::new(x, bottom)),
+            Some((min, max)) => (Point::new(min.x.min(x), min.y.min(top)), Point::new(max.x.max(x), max.y.max(bottom)))
+        });
+    }
+
+    Region {
+        size,
+        extent,
+        connected: size > 0,
+    }
 }
 
-pub fn get_area(points: &Vec<Point>) -> Area {
-    let (width, height) = get_width_height(points);
+// Top left and bottom right corner of the smallest box containing every site.
+pub fn get_bounding_box(sites: &[Site]) -> (Point, Point) {
+    let xs = sites.iter().map(|site| site.point.x);
+    let ys = sites.iter().map(|site| site.point.y);
 
-    let mut area: Area = Vec::new();
-    for y in 0..height + 1 {
-        let mut row: Vec<Option<usize>> = Vec::new();
+    (
+        Point::new(xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+        Point::new(xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+    )
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Owner {
+    Unvisited,
+    Site(usize),
+    Tie,
+}
 
-        for x in 0..width + 2 {
-            match find_closes_point(&Point::new(x, y), points) {
-                Some(p) => row.push(Some(p.id)),
-                None => row.push(None)
+// Labels the box from `min` to `max` grown by `margin` on every side.
+fn label_area<M: Metric>(sites: &[Site], metric: &M, (min, max): (Point, Point), margin: i32) -> Area {
+    let origin = Point::new(min.x - margin, min.y - margin);
+    let width = (max.x - min.x + 1 + 2 * margin) as usize;
+    let height = (max.y - min.y + 1 + 2 * margin) as usize;
+
+    let cells = match metric.flood_steps() {
+        Some(steps) => flood_area(sites, origin, width, height, steps),
+        None => (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let point = Point::new(origin.x + x as i32, origin.y + y as i32);
+                        find_closes_point(&point, sites, metric).map(|site| site.id)
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    Area { origin, cells }
+}
+
+// Floods the box from every site at once. The sites that are nearest to a cell are
+// exactly the sites nearest to the neighbours it was reached from.
+fn flood_area(sites: &[Site], origin: Point, width: usize, height: usize, steps: &[(i32, i32)]) -> Vec<Vec<Option<usize>>> {
+    let mut owners = vec![vec![Owner::Unvisited; width]; height];
+    let mut distances = vec![vec![0; width]; height];
+    let mut queue = VecDeque::new();
+
+    for site in sites {
+        let (x, y) = ((site.point.x - origin.x) as usize, (site.point.y - origin.y) as usize);
+
+        owners[y][x] = match owners[y][x] {
+            Owner::Unvisited => {
+                queue.push_back((x, y));
+                Owner::Site(site.id)
+            }
+            _ => Owner::Tie
+        };
+    }
+
+    while let Some((x, y)) = queue.pop_front() {
+        let owner = owners[y][x];
+        let distance = distances[y][x] + 1;
+        let neighbours = steps
+            .iter()
+            .map(|&(dx, dy)| ((x as i32 + dx) as usize, (y as i32 + dy) as usize))
+            .filter(|&(nx, ny)| nx < width && ny < height);
+
+        for (nx, ny) in neighbours {
+            match owners[ny][nx] {
+                Owner::Unvisited => {
+                    owners[ny][nx] = owner;
+                    distances[ny][nx] = distance;
+                    queue.push_back((nx, ny));
+                }
+                current if distances[ny][nx] == distance && current != owner => {
+                    owners[ny][nx] = Owner::Tie;
+                }
+                _ => {}
             }
         }
-        area.push(row);
     }
 
-    area
+    owners
+        .into_iter()
+        .map(|row| {
+            row
+                .into_iter()
+                .map(|owner| match owner {
+                    Owner::Site(id) => Some(id),
+                    _ => None
+                })
+                .collect()
+        })
+        .collect()
 }
 
-pub fn find_closes_point<'a>(point: &'a Point, points: &'a Vec<Point>) -> Option<&'a Point> {
-    let closest = points
+// Labels the bounding box, growing it until no finite area reaches its edge anymore, so
+// every finite area is complete. For manhattan distance that is never needed.
+//
+// Metrics that can be flooded cost one pass over the final box per growth. Other metrics
+// compare every cell with every site, but they know the extent of the finite areas up
+// front, so that only happens once: O(W·H·N) for the box around the finite areas, after
+// O(N⁴) to find that box.
+pub fn get_area<M: Metric>(sites: &[Site], metric: &M) -> Area {
+    if let Some(extent) = metric.finite_extent(sites) {
+        return label_area(sites, metric, extent, 1);
+    }
+
+    let infinite = get_infinite_areas(sites, metric);
+    let bounding_box = get_bounding_box(sites);
+    let mut margin = 0;
+
+    loop {
+        let area = label_area(sites, metric, bounding_box, margin);
+
+        if area.edge_ids().is_subset(&infinite) {
+            return area;
+        }
+
+        margin = (margin * 2).max(1);
+    }
+}
+
+pub fn find_closes_point<'a, M: Metric>(point: &Point, sites: &'a [Site], metric: &M) -> Option<&'a Site> {
+    let closest = sites
         .iter()
-        .fold((999, None), |closest, p| {
-            let distance = get_distance(p, point);
+        .fold((i64::MAX, None), |closest, site| {
+            let distance = metric.distance(&site.point, point);
 
             match closest.0.cmp(&distance) {
                 Ordering::Less => closest,
-                Ordering::Greater => (distance, Some(p)),
+                Ordering::Greater => (distance, Some(site)),
                 Ordering::Equal => (distance, None)
             }
         });
@@ -210,22 +653,29 @@ mod tests {
     use crate::find_closes_point;
     use crate::count_areas;
     use crate::get_area;
+    use crate::parse_sites;
+    use crate::to_sites;
+    use crate::get_bounding_box;
+    use crate::get_infinite_areas;
+    use crate::{Metric, Manhattan, Chebyshev, SquaredEuclidean, Site};
+    use crate::{safe_region, get_sum_of_distances, Region};
+    use crate::{render_svg, SvgOptions};
 
     #[test]
     fn it_should_count_areas() {
         // Arrange
-        let points = vec![
+        let points = to_sites(vec![
             Point::new(1, 1),
             Point::new(1, 6),
             Point::new(8, 3),
             Point::new(3, 4),
             Point::new(5, 5),
             Point::new(8, 9)
-        ];
+        ]);
 
         // Act
-        let area = get_area(&points);
-        let result = count_areas(&area);
+        let area = get_area(&points, &Manhattan);
+        let result = count_areas(&area, &points, &Manhattan);
 
         assert_eq!(result.len(), 2);
     }
@@ -233,18 +683,18 @@ mod tests {
     #[test]
     fn it_should_find_closes_point() {
         // Arrange
-        let points = vec![
+        let points = to_sites(vec![
             Point::new(1, 1),
             Point::new(1, 6),
             Point::new(8, 3),
             Point::new(3, 4),
             Point::new(5, 5),
             Point::new(8, 9)
-        ];
+        ]);
         let point = Point::new(5, 2);
 
         // Act
-        let result = find_closes_point(&point, &points).unwrap();
+        let result = find_closes_point(&point, &points, &Manhattan).unwrap();
 
         // Assert
         assert_eq!(result, &points[4]);
@@ -253,18 +703,18 @@ mod tests {
     #[test]
     fn it_should_not_find_a_point_when_multiple_are_closest() {
         // Arrange
-        let points = vec![
+        let points = to_sites(vec![
             Point::new(1, 1),
             Point::new(1, 6),
             Point::new(8, 3),
             Point::new(3, 4),
             Point::new(5, 5),
             Point::new(8, 9)
-        ];
+        ]);
         let point = Point::new(1, 4);
 
         // Act
-        let result = find_closes_point(&point, &points);
+        let result = find_closes_point(&point, &points, &Manhattan);
 
         // Assert
         assert_eq!(result, None);
@@ -295,4 +745,197 @@ mod tests {
         assert_eq!(result.x, 300);
         assert_eq!(result.y, 201);
     }
+
+    #[test]
+    fn it_should_number_sites_by_input_order() {
+        // Arrange
+        let input = "1, 1
+1, 6
+8, 3";
+
+        // Act
+        let first = parse_sites(input);
+        let second = parse_sites(input);
+
+        // Assert
+        assert_eq!(first, second);
+        assert_eq!(first[2].id, 2);
+        assert_eq!(first[2].point, Point::new(8, 3));
+    }
+
+    #[test]
+    fn it_should_flood_the_bounding_box() {
+        // Arrange
+        let sites = to_sites(vec![
+            Point::new(101, 101),
+            Point::new(101, 106),
+            Point::new(108, 103),
+            Point::new(103, 104),
+            Point::new(105, 105),
+            Point::new(108, 109),
+            Point::new(104, 107),
+        ]);
+
+        // Act
+        let area = get_area(&sites, &Manhattan);
+        let (min, max) = get_bounding_box(&sites);
+
+        // Assert
+        assert_eq!((min, max), (Point::new(101, 101), Point::new(108, 109)));
+        assert_eq!(area.origin, min);
+        assert_eq!(area.cells.len(), 9);
+        assert_eq!(area.cells[0].len(), 8);
+        for (y, row) in area.cells.iter().enumerate() {
+            for (x, owner) in row.iter().enumerate() {
+                let point = Point::new(min.x + x as i32, min.y + y as i32);
+                let closest = find_closes_point(&point, &sites, &Manhattan).map(|site| site.id);
+
+                assert_eq!(*owner, closest);
+            }
+        }
+    }
+
+    fn assert_infinite_areas_are_unbounded<M: Metric>(sites: &[Site], metric: &M) {
+        let infinite = get_infinite_areas(sites, metric);
+        let far = 1_000;
+
+        for site in sites {
+            let escapes = (-far..=far).any(|i| {
+                [Point::new(i, -far), Point::new(i, far), Point::new(-far, i), Point::new(far, i)]
+                    .iter()
+                    .any(|p| find_closes_point(p, sites, metric).map(|s| s.id) == Some(site.id))
+            });
+
+            assert_eq!(infinite.contains(&site.id), escapes, "site {}", site.id);
+        }
+    }
+
+    fn assert_area_matches_closest_sites<M: Metric>(sites: &[Site], metric: &M) {
+        let area = get_area(sites, metric);
+
+        for (y, row) in area.cells.iter().enumerate() {
+            for (x, owner) in row.iter().enumerate() {
+                let point = Point::new(area.origin.x + x as i32, area.origin.y + y as i32);
+
+                assert_eq!(*owner, find_closes_point(&point, sites, metric).map(|s| s.id));
+            }
+        }
+    }
+
+    #[test]
+    fn it_should_support_every_metric() {
+        // Arrange
+        let sites = to_sites(vec![
+            Point::new(1, 1),
+            Point::new(1, 6),
+            Point::new(8, 3),
+            Point::new(3, 4),
+            Point::new(5, 5),
+            Point::new(8, 9),
+            Point::new(4, 2),
+        ]);
+
+        // Act
+        let manhattan = count_areas(&get_area(&sites, &Manhattan), &sites, &Manhattan);
+
+        // Assert
+        assert_eq!(manhattan.len(), 2);
+        assert_area_matches_closest_sites(&sites, &Manhattan);
+        assert_area_matches_closest_sites(&sites, &Chebyshev);
+        assert_area_matches_closest_sites(&sites, &SquaredEuclidean);
+        assert_infinite_areas_are_unbounded(&sites, &Manhattan);
+        assert_infinite_areas_are_unbounded(&sites, &Chebyshev);
+        assert_infinite_areas_are_unbounded(&sites, &SquaredEuclidean);
+    }
+
+    #[test]
+    fn it_should_size_the_euclidean_area_from_its_corners() {
+        // Arrange
+        let sites = to_sites(vec![
+            Point::new(0, 13),
+            Point::new(13, 1),
+            Point::new(6, 11),
+            Point::new(12, 10),
+            Point::new(0, 6),
+        ]);
+
+        // Act
+        let extent = SquaredEuclidean.finite_extent(&sites);
+        let areas = count_areas(&get_area(&sites, &SquaredEuclidean), &sites, &SquaredEuclidean);
+
+        // Assert
+        assert_eq!(extent, Some((Point::new(0, 1), Point::new(16, 50))));
+        assert_eq!(areas.get(&2), Some(&148));
+        assert_area_matches_closest_sites(&sites, &SquaredEuclidean);
+    }
+
+    #[test]
+    fn it_should_find_the_safe_region() {
+        // Arrange
+        let sites = to_sites(vec![
+            Point::new(1, 1),
+            Point::new(1, 6),
+            Point::new(8, 3),
+            Point::new(3, 4),
+            Point::new(5, 5),
+            Point::new(8, 9)
+        ]);
+
+        // Act
+        let result = safe_region(&sites, 32);
+
+        // Assert
+        assert_eq!(result, Region {
+            size: 16,
+            extent: Some((Point::new(2, 3), Point::new(6, 6))),
+            connected: true,
+        });
+        assert_eq!(safe_region(&sites, 1).size, 0);
+        assert_eq!(safe_region(&sites, 1).extent, None);
+        assert_eq!(safe_region(&[], 3), Region { size: 0, extent: None, connected: false });
+    }
+
+    #[test]
+    fn it_should_grow_the_safe_region_beyond_the_bounding_box() {
+        // Arrange
+        let sites = to_sites(vec![Point::new(0, 0), Point::new(2, 2)]);
+        let threshold = 20;
+        let brute_force = (-20..=20)
+            .flat_map(|x| (-20..=20).map(move |y| Point::new(x, y)))
+            .filter(|p| get_sum_of_distances(&sites, p, &Manhattan) < threshold)
+            .count();
+
+        // Act
+        let result = safe_region(&sites, threshold);
+
+        // Assert
+        assert_eq!(result.size, brute_force as u64);
+        assert_eq!(result.extent, Some((Point::new(-7, -7), Point::new(9, 9))));
+    }
+
+    #[test]
+    fn it_should_render_the_area_as_svg() {
+        // Arrange
+        let sites = to_sites(vec![
+            Point::new(1, 1),
+            Point::new(1, 6),
+            Point::new(8, 3),
+            Point::new(3, 4),
+            Point::new(5, 5),
+            Point::new(8, 9)
+        ]);
+        let area = get_area(&sites, &Manhattan);
+        let options = SvgOptions { cell_size: 10, safe_threshold: Some(32) };
+
+        // Act
+        let svg = render_svg(&area, &sites, &Manhattan, &options);
+
+        // Assert
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"80\" height=\"90\">"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), 6);
+        assert!(svg.contains("fill=\"#999\""));
+        assert!(svg.contains("fill=\"url(#hatch)\""));
+        assert!(svg.contains("class=\"safe\""));
+    }
 }
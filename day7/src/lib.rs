@@ -1,108 +1,408 @@
 use std::str::FromStr;
 use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Node {
-    pub left: char,
-    pub right: char,
+    pub left: String,
+    pub right: String,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct NodeDeps {
-    pub id: char,
-    pub deps: Vec<char>,
+    pub id: String,
+    pub deps: Vec<String>,
+}
+
+type DepList = HashMap<String, HashSet<String>>;
+
+#[derive(Debug, PartialEq)]
+pub enum DepError {
+    // The steps of the cycle, starting and ending with the same step.
+    Cycle(Vec<String>),
+    DanglingStep { step: String, required_by: String },
+    DuplicateEdge(Node),
+    InvalidLine(String),
+    NoWorkers,
+    UnknownDuration(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScheduledStep {
+    pub step: String,
+    pub worker: usize,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Schedule {
+    pub steps: Vec<ScheduledStep>,
+    pub duration: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StepTiming {
+    pub step: String,
+    pub duration: u32,
+    pub earliest_start: u32,
+    pub latest_start: u32,
+    pub slack: u32,
 }
 
-type DepList = HashMap<char, HashSet<char>>;
+#[derive(Debug, PartialEq)]
+pub struct CriticalPath {
+    // In build order.
+    pub timings: Vec<StepTiming>,
+    pub steps: Vec<String>,
+    pub duration: u32,
+    // The smallest pool with which `schedule_sleigh` finishes within `duration`. It hands
+    // out steps alphabetically, so a schedule that prefers the steps with the least slack
+    // might manage with fewer workers.
+    pub workers_needed: usize,
+}
 
 impl FromStr for Node {
     type Err = ();
 
     fn from_str(input: &str) -> Result<Node, ()> {
         let re =
-            Regex::new(r"Step (?P<left>.) must be finished before step (?P<right>.) .*$").unwrap();
+            Regex::new(r"Step (?P<left>\S+) must be finished before step (?P<right>\S+) .*$").unwrap();
 
 
         match re.captures(input) {
             Some(caps) => Ok(Node {
-                left: caps["left"].parse().unwrap(),
-                right: caps["right"].parse().unwrap(),
+                left: caps["left"].to_string(),
+                right: caps["right"].to_string(),
             }),
             None => Err(())
         }
     }
 }
 
-pub fn order_build_steps_sleigh(deps: &mut DepList) -> String {
-    let mut result = String::new();
+pub fn order_build_steps_sleigh(deps: &mut DepList) -> Result<Vec<String>, DepError> {
+    validate_deps(deps)?;
+
+    let mut result = vec![];
     while !deps.is_empty() {
-        let next_step = find_next_available_step(&deps).unwrap();
+        let next_step = find_next_available_step(deps)
+            .ok_or_else(|| DepError::Cycle(find_cycle(deps).unwrap_or_default()))?;
+        remove_step_from_all_deps(&next_step, deps);
+        remove_step(&next_step, deps);
         result.push(next_step);
-        remove_step_from_all_deps(next_step, deps);
-        remove_step(next_step, deps);
     }
 
-    result
+    Ok(result)
+}
+
+// Checks for steps that are required but never defined, and for cycles. A `DepList` can't
+// hold the same requirement twice, so duplicate edges are only caught by
+// `to_validated_nodes`, which still sees the instructions themselves.
+pub fn validate_deps(deps: &DepList) -> Result<(), DepError> {
+    let mut steps: Vec<&String> = deps.keys().collect();
+    steps.sort();
+
+    for step in steps {
+        let mut required: Vec<&String> = deps[step].iter().collect();
+        required.sort();
+
+        if let Some(dangling) = required.into_iter().find(|dep| !deps.contains_key(*dep)) {
+            return Err(DepError::DanglingStep { step: dangling.clone(), required_by: step.clone() });
+        }
+    }
+
+    match find_cycle(deps) {
+        Some(cycle) => Err(DepError::Cycle(cycle)),
+        None => Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+// Depth first search over the dependencies. Running into a step that is still on the
+// stack means the stack from that step onwards is a cycle.
+fn find_cycle(deps: &DepList) -> Option<Vec<String>> {
+    fn visit<'a>(step: &'a str, deps: &'a DepList, visits: &mut HashMap<&'a str, Visit>, stack: &mut Vec<&'a str>) -> Option<Vec<String>> {
+        match visits.get(step) {
+            Some(Visit::Done) => return None,
+            Some(Visit::InProgress) => {
+                let start = stack.iter().position(|s| *s == step).unwrap();
+                let mut cycle: Vec<String> = stack[start..].iter().map(|s| s.to_string()).collect();
+                cycle.push(step.to_string());
+
+                return Some(cycle);
+            }
+            None => {}
+        }
+
+        visits.insert(step, Visit::InProgress);
+        stack.push(step);
+
+        let mut required: Vec<&String> = deps.get(step).map_or(vec![], |set| set.iter().collect());
+        required.sort();
+
+        for dep in required {
+            if let Some(cycle) = visit(dep, deps, visits, stack) {
+                return Some(cycle);
+            }
+        }
+
+        stack.pop();
+        visits.insert(step, Visit::Done);
+
+        None
+    }
+
+    let mut steps: Vec<&String> = deps.keys().collect();
+    steps.sort();
+
+    let mut visits = HashMap::new();
+    steps
+        .into_iter()
+        .find_map(|step| visit(step, deps, &mut visits, &mut vec![]))
 }
 
 
 const CHARS: [char; 26] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z'];
 
 
-pub fn get_time_for_step(c: char, base_time: u32) -> u32 {
-    let time = CHARS.iter().position(|char1| c == *char1).unwrap();
+// Only single letter steps have a place in the alphabet, other steps have no time.
+pub fn get_time_for_step(step: &str, base_time: u32) -> Option<u32> {
+    let mut chars = step.chars();
+    let c = match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => return None
+    };
+    let time = CHARS.iter().position(|char1| c == *char1)?;
+
+    Some((time as u32) + 1 + base_time)
+}
+
+pub trait DurationSource {
+    fn duration(&self, step: &str) -> Option<u32>;
+}
+
+// The puzzle's durations: a base time plus the position of the step in the alphabet.
+pub struct AlphabetOffset {
+    pub base_time: u32,
+}
+
+impl DurationSource for AlphabetOffset {
+    fn duration(&self, step: &str) -> Option<u32> {
+        get_time_for_step(step, self.base_time)
+    }
+}
+
+// Durations read from a table file with a step name and its seconds on every line. Blank
+// lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct DurationTable {
+    durations: HashMap<String, u32>,
+}
+
+impl DurationTable {
+    pub fn new() -> DurationTable {
+        DurationTable::default()
+    }
+
+    pub fn insert(&mut self, step: &str, seconds: u32) {
+        self.durations.insert(step.to_string(), seconds);
+    }
+}
+
+impl DurationSource for DurationTable {
+    fn duration(&self, step: &str) -> Option<u32> {
+        self.durations.get(step).cloned()
+    }
+}
+
+impl FromStr for DurationTable {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<DurationTable, String> {
+        let mut table = DurationTable::new();
+
+        for (number, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match (parts.len(), parts.last().and_then(|seconds| seconds.parse().ok())) {
+                (2, Some(seconds)) => table.insert(parts[0], seconds),
+                _ => return Err(format!("invalid duration on line {}: {}", number + 1, line))
+            }
+        }
+
+        Ok(table)
+    }
+}
 
-    (time as u32) + 1 + base_time
+// Takes the duration from `overrides` when it has one, and from `source` otherwise.
+pub struct WithOverrides<S: DurationSource> {
+    pub source: S,
+    pub overrides: DurationTable,
 }
 
-pub fn build_sleigh(deps: &mut DepList, workers_amount: u8, base_time: u32) -> u32 {
+impl<S: DurationSource> DurationSource for WithOverrides<S> {
+    fn duration(&self, step: &str) -> Option<u32> {
+        self.overrides.duration(step).or_else(|| self.source.duration(step))
+    }
+}
+
+pub fn build_sleigh(deps: &DepList, workers_amount: usize, base_time: u32) -> Result<u32, DepError> {
+    schedule_sleigh(deps, workers_amount, &AlphabetOffset { base_time }).map(|schedule| schedule.duration)
+}
+
+// Hands out the available steps alphabetically to the idle workers, lowest worker first,
+// then jumps straight to the moment the next step is finished. Steps finishing at the
+// same moment are all completed before anything new is handed out.
+pub fn schedule_sleigh<D: DurationSource>(deps: &DepList, workers_amount: usize, durations: &D) -> Result<Schedule, DepError> {
+    validate_deps(deps)?;
+    if workers_amount == 0 && !deps.is_empty() {
+        return Err(DepError::NoWorkers);
+    }
+
+    let seconds_needed = get_durations(deps, durations)?;
+
+    let mut deps = deps.clone();
+    let mut idle: BinaryHeap<Reverse<usize>> = (0..workers_amount).map(Reverse).collect();
+    let mut in_progress: BinaryHeap<Reverse<(u32, usize, String)>> = BinaryHeap::new();
+    let mut steps = vec![];
     let mut time = 0;
-    let mut workers: Vec<(Option<char>, u32)> = vec![(None, 0); workers_amount as usize];
-
-    while !deps.is_empty() || workers.iter().any(|(working, _)| working != &None) {
-        workers
-            .iter_mut()
-            .for_each(|worker| {
-                match worker.0 {
-                    Some(c) => {
-                        worker.1 -= 1;
-                        if worker.1 == 0 {
-                            remove_step_from_all_deps(c, deps);
-
-                            match find_next_available_step(&deps) {
-                                Some(next_step) => {
-                                    remove_step(next_step, deps);
-                                    let seconds_needed = get_time_for_step(next_step, base_time);
-                                    worker.0 = Some(next_step);
-                                    worker.1 = seconds_needed;
-                                }
-                                None => {
-                                    worker.0 = None
-                                }
-                            };
-                        }
-                    }
-                    None => {
-                        match find_next_available_step(&deps) {
-                            Some(next_step) => {
-                                remove_step(next_step, deps);
-                                let seconds_needed = get_time_for_step(next_step, base_time);
-                                worker.0 = Some(next_step);
-                                worker.1 = seconds_needed;
-                            }
-                            None => {}
-                        };
-                    }
-                }
-            });
 
-        time += 1;
+    loop {
+        while !idle.is_empty() {
+            let next_step = match find_next_available_step(&deps) {
+                Some(next_step) => next_step,
+                None => break
+            };
+            let Reverse(worker) = idle.pop().unwrap();
+            let end = time + seconds_needed[&next_step];
+
+            remove_step(&next_step, &mut deps);
+            in_progress.push(Reverse((end, worker, next_step.clone())));
+            steps.push(ScheduledStep { step: next_step, worker, start: time, end });
+        }
+
+        time = match in_progress.peek() {
+            Some(Reverse((end, _, _))) => *end,
+            None => break
+        };
+
+        while in_progress.peek().is_some_and(|Reverse((end, _, _))| *end == time) {
+            let Reverse((_, worker, step)) = in_progress.pop().unwrap();
+
+            remove_step_from_all_deps(&step, &mut deps);
+            idle.push(Reverse(worker));
+        }
+    }
+
+    Ok(Schedule { steps, duration: time })
+}
+
+fn get_durations<D: DurationSource>(deps: &DepList, durations: &D) -> Result<HashMap<String, u32>, DepError> {
+    let mut names: Vec<&String> = deps.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|step| match durations.duration(step) {
+            Some(seconds) => Ok((step.clone(), seconds)),
+            None => Err(DepError::UnknownDuration(step.clone()))
+        }).collect()
+}
+
+pub fn find_critical_path<D: DurationSource>(deps: &DepList, durations: &D) -> Result<CriticalPath, DepError> {
+    let order = order_build_steps_sleigh(&mut deps.clone())?;
+    let seconds_needed = get_durations(deps, durations)?;
+
+    let mut required_by: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (step, set) in deps.iter() {
+        for dep in set {
+            required_by.entry(dep.as_str()).or_default().push(step.as_str());
+        }
+    }
+
+    let mut earliest: HashMap<&str, u32> = HashMap::new();
+    for step in &order {
+        let start = deps[step]
+            .iter()
+            .map(|dep| earliest[dep.as_str()] + seconds_needed[dep])
+            .max()
+            .unwrap_or(0);
+        earliest.insert(step, start);
+    }
+
+    let duration = order
+        .iter()
+        .map(|step| earliest[step.as_str()] + seconds_needed[step])
+        .max()
+        .unwrap_or(0);
+
+    let mut latest: HashMap<&str, u32> = HashMap::new();
+    for step in order.iter().rev() {
+        let end = required_by
+            .get(step.as_str())
+            .and_then(|next| next.iter().map(|next| latest[next]).min())
+            .unwrap_or(duration);
+        latest.insert(step, end - seconds_needed[step]);
+    }
+
+    let timings: Vec<StepTiming> = order
+        .iter()
+        .map(|step| StepTiming {
+            step: step.clone(),
+            duration: seconds_needed[step],
+            earliest_start: earliest[step.as_str()],
+            latest_start: latest[step.as_str()],
+            slack: latest[step.as_str()] - earliest[step.as_str()],
+        }).collect();
+
+    // Follow the steps without slack, each one starting the moment the previous one ends.
+    // When there are several critical paths the alphabetically first is taken.
+    let is_critical = |step: &str, start: u32| latest[step] == earliest[step] && earliest[step] == start;
+    let mut steps: Vec<String> = vec![];
+    let mut candidates: Vec<&str> = order
+        .iter()
+        .map(|step| step.as_str())
+        .filter(|step| deps[*step].is_empty())
+        .collect();
+    let mut time = 0;
+    loop {
+        candidates.sort_unstable();
+        let next = match candidates.into_iter().find(|step| is_critical(step, time)) {
+            Some(next) => next,
+            None => break
+        };
+
+        time += seconds_needed[next];
+        steps.push(next.to_string());
+        candidates = required_by.get(next).cloned().unwrap_or_default();
     }
 
+    // `schedule_sleigh` can even get slower with more workers, so every pool size is
+    // tried. With a worker per step nobody ever waits for a worker, which always reaches
+    // the critical path.
+    let total: u32 = seconds_needed.values().sum();
+    let lower_bound = if duration == 0 { deps.len().min(1) } else { total.div_ceil(duration) as usize };
+    let mut workers_needed = deps.len();
+    for workers_amount in lower_bound..deps.len() {
+        if schedule_sleigh(deps, workers_amount, durations)?.duration == duration {
+            workers_needed = workers_amount;
+            break;
+        }
+    }
 
-    time - 1
+    Ok(CriticalPath { timings, steps, duration, workers_needed })
 }
 
 pub fn find_first_char_in_set(set: &HashSet<char>) -> Option<char> {
@@ -122,58 +422,79 @@ pub fn find_first_char_in_set(set: &HashSet<char>) -> Option<char> {
         })
 }
 
-pub fn remove_step(c: char, deps: &mut DepList) {
-    deps.remove(&c);
+pub fn remove_step(step: &str, deps: &mut DepList) {
+    deps.remove(step);
 }
 
-pub fn remove_step_from_all_deps(c: char, deps: &mut DepList) {
+pub fn remove_step_from_all_deps(step: &str, deps: &mut DepList) {
     deps
         .iter_mut()
         .for_each(|(_id, set)| {
-            set.remove(&c);
+            set.remove(step);
         })
 }
 
-pub fn find_available_steps(deps: &DepList) -> Vec<char> {
+pub fn find_available_steps(deps: &DepList) -> Vec<String> {
     deps
         .iter()
         .filter_map(|(id, set)| {
-            if set.len() == 0 {
-                Some(*id)
+            if set.is_empty() {
+                Some(id.clone())
             } else {
                 None
             }
         }).collect()
 }
 
-pub fn find_next_available_step(deps: &DepList) -> Option<char> {
-    let mut steps_with_no_deps: Vec<char> = find_available_steps(&deps);
+pub fn find_next_available_step(deps: &DepList) -> Option<String> {
+    let mut steps_with_no_deps: Vec<String> = find_available_steps(deps);
 
     steps_with_no_deps.sort_unstable();
 
-    match steps_with_no_deps.first() {
-        Some(c) => Some(*c),
-        None => None
+    steps_with_no_deps.into_iter().next()
+}
+
+// Like `to_nodes`, but refuses instructions that list the same requirement twice or
+// that can never be completed.
+pub fn to_validated_nodes(input: &str) -> Result<DepList, DepError> {
+    let nodes = parse_nodes(input)?;
+    let mut seen = HashSet::new();
+
+    for node in &nodes {
+        if !seen.insert((&node.left, &node.right)) {
+            return Err(DepError::DuplicateEdge(node.clone()));
+        }
     }
+
+    let deps = nodes_to_deps(&nodes);
+    validate_deps(&deps)?;
+
+    Ok(deps)
 }
 
-pub fn to_nodes(input: &str) -> DepList {
-    let nodes: Vec<Node> = input
+pub fn to_nodes(input: &str) -> Result<DepList, DepError> {
+    parse_nodes(input).map(|nodes| nodes_to_deps(&nodes))
+}
+
+fn parse_nodes(input: &str) -> Result<Vec<Node>, DepError> {
+    input
         .lines()
-        .map(|l| l.parse().unwrap())
-        .collect();
+        .map(|line| line.trim().parse().map_err(|_| DepError::InvalidLine(line.trim().to_string())))
+        .collect()
+}
 
+fn nodes_to_deps(nodes: &[Node]) -> DepList {
     let mut deps: DepList = HashMap::new();
     nodes
         .iter()
         .for_each(|node| {
             let d = deps
-                .entry(node.right)
-                .or_insert(HashSet::new());
+                .entry(node.right.clone())
+                .or_default();
 
-            d.insert(node.left);
+            d.insert(node.left.clone());
 
-            deps.entry(node.left).or_insert(HashSet::new());
+            deps.entry(node.left.clone()).or_default();
         });
 
     deps
@@ -186,6 +507,16 @@ mod tests {
     use crate::find_next_available_step;
     use crate::order_build_steps_sleigh;
     use crate::build_sleigh;
+    use crate::{to_validated_nodes, validate_deps, DepError};
+    use crate::{schedule_sleigh, ScheduledStep};
+    use crate::{AlphabetOffset, DurationTable, WithOverrides};
+    use crate::{find_critical_path, StepTiming};
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+
+    fn steps(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
 
     #[test]
     fn it_should_parse_input() {
@@ -196,7 +527,7 @@ mod tests {
         let result: Node = input.parse().unwrap();
 
         // Assert
-        assert_eq!(result, Node { left: 'C', right: 'A' });
+        assert_eq!(result, Node { left: "C".to_string(), right: "A".to_string() });
     }
 
     #[test]
@@ -211,11 +542,11 @@ mod tests {
         Step F must be finished before step E can begin.";
 
         // Act
-        let deps = to_nodes(input);
+        let deps = to_nodes(input).unwrap();
         let result = find_next_available_step(&deps).unwrap();
 
         // Assert
-        assert_eq!(result, 'C');
+        assert_eq!(result, "C");
     }
 
 
@@ -231,11 +562,11 @@ mod tests {
         Step F must be finished before step E can begin.";
 
         // Act
-        let mut deps = to_nodes(input);
-        let result = order_build_steps_sleigh(&mut deps);
+        let mut deps = to_nodes(input).unwrap();
+        let result = order_build_steps_sleigh(&mut deps).unwrap();
 
         // Assert
-        assert_eq!(result, "CABDFE");
+        assert_eq!(result.join(""), "CABDFE");
     }
 
     #[test]
@@ -250,10 +581,157 @@ mod tests {
         Step F must be finished before step E can begin.";
 
         // Act
-        let mut deps = to_nodes(input);
-        let result = build_sleigh(&mut deps, 2, 0);
+        let deps = to_nodes(input).unwrap();
+        let result = build_sleigh(&deps, 2, 0).unwrap();
 
         // Assert
         assert_eq!(result, 15);
     }
+
+    #[test]
+    fn it_should_report_cycles() {
+        // Arrange
+        let input = "Step C must be finished before step A can begin.
+        Step A must be finished before step B can begin.
+        Step B must be finished before step D can begin.
+        Step D must be finished before step A can begin.";
+
+        // Act
+        let mut deps = to_nodes(input).unwrap();
+        let result = order_build_steps_sleigh(&mut deps);
+
+        // Assert
+        assert_eq!(result, Err(DepError::Cycle(steps(&["A", "D", "B", "A"]))));
+        assert_eq!(build_sleigh(&to_nodes(input).unwrap(), 2, 0), Err(DepError::Cycle(steps(&["A", "D", "B", "A"]))));
+    }
+
+    #[test]
+    fn it_should_report_dangling_steps_and_duplicate_edges() {
+        // Arrange
+        let mut deps = HashMap::new();
+        deps.insert("A".to_string(), HashSet::new());
+        deps.insert("B".to_string(), steps(&["A", "X"]).into_iter().collect());
+        let input = "Step C must be finished before step A can begin.
+        Step C must be finished before step A can begin.";
+
+        // Act
+        let dangling = validate_deps(&deps);
+        let duplicate = to_validated_nodes(input);
+
+        // Assert
+        assert_eq!(dangling, Err(DepError::DanglingStep { step: "X".to_string(), required_by: "B".to_string() }));
+        assert_eq!(duplicate, Err(DepError::DuplicateEdge(Node { left: "C".to_string(), right: "A".to_string() })));
+    }
+
+    #[test]
+    fn it_should_report_invalid_lines() {
+        // Arrange
+        let input = "Step C must be finished before step A can begin.
+        Step C must be done first.";
+
+        // Act
+        let result = to_nodes(input);
+        let validated = to_validated_nodes(input);
+
+        // Assert
+        assert_eq!(result, Err(DepError::InvalidLine("Step C must be done first.".to_string())));
+        assert_eq!(validated, Err(DepError::InvalidLine("Step C must be done first.".to_string())));
+    }
+
+    #[test]
+    fn it_should_schedule_every_step() {
+        // Arrange
+        let input = "Step C must be finished before step A can begin.
+        Step C must be finished before step F can begin.
+        Step A must be finished before step B can begin.
+        Step A must be finished before step D can begin.
+        Step B must be finished before step E can begin.
+        Step D must be finished before step E can begin.
+        Step F must be finished before step E can begin.";
+
+        // Act
+        let deps = to_nodes(input).unwrap();
+        let result = schedule_sleigh(&deps, 2, &AlphabetOffset { base_time: 0 }).unwrap();
+
+        // Assert
+        assert_eq!(result.duration, 15);
+        assert_eq!(result.steps, vec![
+            ScheduledStep { step: "C".to_string(), worker: 0, start: 0, end: 3 },
+            ScheduledStep { step: "A".to_string(), worker: 0, start: 3, end: 4 },
+            ScheduledStep { step: "F".to_string(), worker: 1, start: 3, end: 9 },
+            ScheduledStep { step: "B".to_string(), worker: 0, start: 4, end: 6 },
+            ScheduledStep { step: "D".to_string(), worker: 0, start: 6, end: 10 },
+            ScheduledStep { step: "E".to_string(), worker: 0, start: 10, end: 15 },
+        ]);
+        assert_eq!(schedule_sleigh(&deps, 0, &AlphabetOffset { base_time: 0 }), Err(DepError::NoWorkers));
+        assert_eq!(build_sleigh(&deps, 300, 0), Ok(14));
+    }
+
+    #[test]
+    fn it_should_schedule_named_steps_with_duration_tables() {
+        // Arrange
+        let input = "Step fetch must be finished before step compile can begin.
+        Step compile must be finished before step test can begin.
+        Step compile must be finished before step package can begin.
+        Step lint must be finished before step package can begin.";
+        let table: DurationTable = "# seconds per step
+        fetch 5
+        compile 30
+        test 20
+        lint 3
+        package 4".parse().unwrap();
+        let mut overrides = DurationTable::new();
+        overrides.insert("compile", 10);
+
+        // Act
+        let deps = to_nodes(input).unwrap();
+        let order = order_build_steps_sleigh(&mut to_nodes(input).unwrap()).unwrap();
+        let result = schedule_sleigh(&deps, 2, &table).unwrap();
+        let overridden = schedule_sleigh(&deps, 2, &WithOverrides { source: table, overrides }).unwrap();
+
+        // Assert
+        assert_eq!(order, steps(&["fetch", "compile", "lint", "package", "test"]));
+        assert_eq!(result.duration, 55);
+        assert_eq!(overridden.duration, 35);
+        assert_eq!(schedule_sleigh(&deps, 2, &AlphabetOffset { base_time: 0 }), Err(DepError::UnknownDuration("compile".to_string())));
+        assert!("fetch five".parse::<DurationTable>().is_err());
+    }
+
+    #[test]
+    fn it_should_find_the_critical_path() {
+        // Arrange
+        let input = "Step C must be finished before step A can begin.
+        Step C must be finished before step F can begin.
+        Step A must be finished before step B can begin.
+        Step A must be finished before step D can begin.
+        Step B must be finished before step E can begin.
+        Step D must be finished before step E can begin.
+        Step F must be finished before step E can begin.";
+        let deps = to_nodes(input).unwrap();
+
+        // Act
+        let result = find_critical_path(&deps, &AlphabetOffset { base_time: 0 }).unwrap();
+
+        // Assert
+        let timing = |step: &str, duration, earliest_start, latest_start| StepTiming {
+            step: step.to_string(),
+            duration,
+            earliest_start,
+            latest_start,
+            slack: latest_start - earliest_start,
+        };
+        assert_eq!(result.timings, vec![
+            timing("C", 3, 0, 0),
+            timing("A", 1, 3, 4),
+            timing("B", 2, 4, 7),
+            timing("D", 4, 4, 5),
+            timing("F", 6, 3, 3),
+            timing("E", 5, 9, 9),
+        ]);
+        assert_eq!(result.steps, steps(&["C", "F", "E"]));
+        assert_eq!(result.duration, 14);
+        assert_eq!(result.workers_needed, 3);
+        assert_eq!(schedule_sleigh(&deps, result.workers_needed - 1, &AlphabetOffset { base_time: 0 }).unwrap().duration, 15);
+        assert_eq!(find_critical_path(&HashMap::new(), &AlphabetOffset { base_time: 0 }).unwrap().workers_needed, 0);
+    }
 }